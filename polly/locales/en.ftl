@@ -0,0 +1,192 @@
+error-reply = 😵‍💫 Something went wrong. I'll let my admins know about it.
+reply-attached = This is too long for a message, so I attached it as a file.
+duration-days = { $days }d { $hours }h
+duration-hours = { $hours }h { $minutes }m
+duration-minutes = { $minutes }m
+
+## Intros
+
+intro-button = Introduce yourself
+intro-modal-title = Introduce yourself
+intro-message = Introduction: { $user }
+intro-submitted = Thanks for submitting your introduction. In the next few seconds, you'll get access to the rest of the server.
+intro-updated = Introduction updated { $url }
+intro-restore-button = Restore my previous introduction
+intro-restore-none = You have no previous introduction to restore.
+intro-check-rejected = Your introduction wasn't posted, because it looks like spam or low effort. Please write a bit about yourself in your own words and try again:
+intro-check-repeated = It contains a lot of repeated characters.
+intro-check-invite = It contains an invite to another server.
+intro-check-link = It contains a link.
+intro-check-duplicate = It's very similar to someone else's introduction.
+intro-check-duplicate-of = It's very similar to the introduction of { $member }.
+intro-check-blocked-word = It contains the word “{ $word }”.
+intro-check-report = The introduction of { $member } (`{ $tag }`) may be spam or low effort: { $location }
+intro-check-unpublished = awaiting review
+view-none = { $user } has not introduced themselves yet.
+view-link = Introduction: { $link }
+view-unpublished = This introduction is not published in the intros channel.
+search-none = No introductions match “{ $query }”.
+search-title = **Introductions matching “{ $query }”** ({ $first }–{ $last } of { $count })
+search-previous = Previous
+search-next = Next
+
+mydata-export-dm = Here is everything Polly stores about you.
+mydata-export-sent = I sent you a DM with your data.
+mydata-export-dm-failed = I couldn't DM you. Please allow DMs from server members and try again.
+mydata-delete-confirm =
+    This deletes your introduction and everything else Polly stores about you, in all servers.
+    If you're still a member of a server, you'll be quarantined again, and lose access to it until you introduce yourself again.
+mydata-delete-button = Delete my data
+mydata-cancel-button = Cancel
+mydata-delete-cancelled = Nothing was deleted.
+mydata-delete-in-progress = Deleting your data…
+mydata-deleted = Your data was deleted.
+
+## Quarantine
+
+age-button = I'm 18 or older
+age-required = This server is only for adults. Please confirm that you're 18 or older before introducing yourself.
+age-confirmed = Thanks for confirming. You can introduce yourself now.
+rules-button = I agree to the rules
+rules-required = Please read the rules and agree to them before introducing yourself.
+rules-outdated = The rules have changed since. Please read the current rules and agree to them.
+lockdown-paused = We're not accepting introductions right now. Please try again later. You won't be kicked in the meantime.
+rules-accepted = Thanks for agreeing to the rules.
+rules-accepted-intro = Thanks for agreeing to the rules. You can introduce yourself now.
+rules-changed-dm = The rules of { $guild } have changed. Please read them and agree to them.
+
+welcome-awaiting-review = Awaiting review
+welcome-thread-name = Welcome { $user }
+reminder-ping = { $member }, please introduce yourself { $deadline }, or you'll be removed from the server.
+reminder-dm =
+    You still need to introduce yourself in { $guild }. If you don't do so { $deadline }, you'll be removed from the server.
+    { $link }
+kick-dm = You were kicked from { $guild } because you did not submit an introduction. You can join again using an invite link.
+# Shows in the audit log.
+kick-reason = Onboarding not completed
+
+## Review
+
+review-submitted = Thanks for submitting your introduction. A moderator will review it soon, and then you'll get access to the rest of the server.
+review-message = Introduction from { $member } awaiting review
+review-approve = Approve
+review-reject = Reject
+review-request-changes = Request changes
+review-reject-title = Reject introduction
+review-request-changes-title = Request changes
+review-reason = Reason
+review-reason-placeholder = This is sent to the member
+review-not-moderator = Only moderators can review introductions.
+review-not-pending = This introduction is no longer awaiting review.
+review-already-handled = Another moderator already handled this introduction.
+review-age-not-confirmed = { $member } can't be approved before they confirm their age.
+review-rules-not-accepted = { $member } can't be approved before they accept the current rules.
+review-approval-failed = Approving the introduction from { $member } didn't finish: { $error }
+review-approved = Introduction from { $member } approved by { $moderator }
+review-rejected =
+    Introduction from { $member } rejected by { $moderator }
+    > { $reason }
+review-changes-requested =
+    Introduction from { $member } returned for changes by { $moderator }
+    > { $reason }
+review-dm-failed = ⚠️ I couldn't send them a DM.
+review-withdrawn = Introduction from { $member } withdrawn because they left
+review-rejected-dm =
+    Your introduction to { $guild } was not approved.
+    > { $reason }
+    You can submit a new introduction here: { $link }
+# Used in the DMs when the welcome message is gone.
+review-dm-location = in { $guild }
+review-changes-requested-dm =
+    A moderator asked you to make some changes to your introduction to { $guild }.
+    > { $reason }
+    You can edit your introduction here: { $link }
+
+## Moderation commands
+
+rules-report =
+    { $count ->
+        [one] 1 member hasn't agreed to version { $version } of the rules, published { $published }:
+       *[other] { $count } members haven't agreed to version { $version } of the rules, published { $published }:
+    }
+rules-report-member = - { $member } (`{ $tag }`)
+
+age-lookup = { $user } confirmed being 18 or older { $confirmed_at }.
+age-lookup-none = { $user } has not confirmed being 18 or older.
+
+history-none = { $user } has never submitted an introduction.
+history-out-of-range =
+    { $count ->
+        [one] { $user } only has 1 revision of their introduction.
+       *[other] { $user } only has { $count } revisions of their introduction.
+    }
+history-title = **Introduction revisions of { $user }**
+history-changes-title = **Changes from revision { $from } to { $to }**
+history-no-changes = No changes
+
+preview-summary = **Dry run** of { $members } members: { $quarantine } would be quarantined, { $kick } would be kicked
+preview-kick = Kick
+preview-quarantine = Quarantine
+preview-member = - **{ $action }** { $member } (`{ $tag }`): joined { $joined }, roles: { $roles }, intro: { $introduced }
+preview-unknown = unknown
+preview-none = none
+preview-yes = yes
+preview-no = no
+
+stats-title =
+    { $weeks ->
+        [one] **Onboarding of members who joined this week**
+       *[other] **Onboarding of members who joined in the last { $weeks } weeks**
+    }
+stats-none =
+    { $weeks ->
+        [one] No members joined this week.
+       *[other] No members joined in the last { $weeks } weeks.
+    }
+stats-week = __Week of { $week }__
+stats-total = __Total__
+stats-funnel = { $joined } joined, { $welcomed } welcomed, { $introduced } introduced ({ $introduced_percent }%), { $unquarantined } completed onboarding ({ $unquarantined_percent }%), { $kicked } kicked, { $left } left
+stats-median = Median time from joining to introduction: { $duration }
+
+lockdown-alert = **Possible join raid:** { $count } members joined in the last { $window }. Onboarding is locked down: { $action } End the lockdown with `/onboarding lockdown end`.
+lockdown-action-alert = quarantined members can onboard as usual.
+lockdown-action-pause-intros = quarantined members can't introduce themselves, and aren't kicked.
+lockdown-action-hold-for-review = intros of quarantined members must be approved by a moderator.
+lockdown-started = Onboarding is locked down: { $action }
+lockdown-already-active = Onboarding is already locked down.
+lockdown-not-configured = Lockdowns aren't configured for this server.
+lockdown-ended = The lockdown has ended.
+lockdown-not-active = Onboarding isn't locked down.
+
+moderation-not-allowed = Only members with the { $role } role can use this command.
+moderation-no-reason = No reason given
+moderation-already-quarantined = { $member } is already quarantined.
+moderation-quarantined = { $member } was quarantined.
+moderation-quarantined-report = { $moderator } quarantined { $member } (`{ $tag }`). Reason: { $reason }
+moderation-not-quarantined = { $member } isn't quarantined.
+moderation-age-not-confirmed = { $member } can't be released before they confirm their age.
+moderation-rules-not-accepted = { $member } can't be released before they accept the rules.
+moderation-released = { $member } was released from quarantine.
+moderation-released-report = { $moderator } released { $member } (`{ $tag }`) from quarantine.
+moderation-reset-intro = The introduction of { $member } was deleted, and they have to introduce themselves again.
+moderation-reset-intro-report = { $moderator } deleted the introduction of { $member } (`{ $tag }`). Reason: { $reason }
+
+state-none = Polly hasn't recorded an onboarding state for { $user }.
+state-current = { $user }: **{ $state }** since { $since }
+state-joined = Joined
+state-welcomed = Welcomed
+state-intro-submitted = Intro submitted
+state-under-review = Under review
+state-member = Member
+state-kicked = Kicked
+state-left = Left
+
+deleted-intro-report = The introduction of { $member } (`{ $tag }`) was deleted, so they have to introduce themselves again.
+deleted-welcome-report = The welcome message of { $member } (`{ $tag }`) was deleted, so it was sent again.
+
+new-account-report = { $member } (`{ $tag }`) joined with an account created { $age } ago. Their introduction will need to be approved by a moderator.
+
+sync-db-no-mentions = Intro message has no mentions: { $link }
+sync-db-duplicate = Duplicate intro messages: { $first } { $second }
+sync-db-import-failed = Could not import intro { $link }: { $error }
+sync-db-summary = Intros: added { $added }, deleted { $deleted }, imported { $imported }
@@ -0,0 +1,8 @@
+create table onboarding_intros (
+    guild_id bigint not null,
+    user_id bigint not null,
+    fields jsonb not null,
+    submitted_at timestamptz not null,
+    updated_at timestamptz not null,
+    primary key (guild_id, user_id)
+)
@@ -0,0 +1,13 @@
+create table onboarding_intro_revisions (
+    id bigint generated always as identity primary key,
+    guild_id bigint not null,
+    user_id bigint not null,
+    fields jsonb not null,
+    created_at timestamptz not null
+);
+
+create index on onboarding_intro_revisions (guild_id, user_id, created_at);
+
+insert into onboarding_intro_revisions (guild_id, user_id, fields, created_at)
+select guild_id, user_id, fields, updated_at
+from onboarding_intros;
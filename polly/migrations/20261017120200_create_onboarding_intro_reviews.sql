@@ -0,0 +1,10 @@
+create table onboarding_intro_reviews (
+    guild_id bigint not null,
+    user_id bigint not null,
+    fields jsonb not null,
+    channel_id bigint not null,
+    message_id bigint not null,
+    changes_requested boolean not null default false,
+    submitted_at timestamptz not null,
+    primary key (guild_id, user_id)
+)
@@ -0,0 +1,9 @@
+create table onboarding_reminders (
+    guild_id bigint not null,
+    user_id bigint not null,
+    offset_secs bigint not null,
+    channel_id bigint not null,
+    message_id bigint not null,
+    sent_at timestamptz not null,
+    primary key (guild_id, user_id, offset_secs)
+)
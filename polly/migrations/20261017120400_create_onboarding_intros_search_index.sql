@@ -0,0 +1,2 @@
+-- The simple configuration doesn't stem words, because intros are written in many languages.
+create index on onboarding_intros using gin (to_tsvector('simple', fields));
@@ -0,0 +1,6 @@
+create table onboarding_age_confirmations (
+    guild_id bigint not null,
+    user_id bigint not null,
+    confirmed_at timestamptz not null,
+    primary key (guild_id, user_id)
+)
@@ -0,0 +1,16 @@
+create table onboarding_rules_versions (
+    guild_id bigint not null,
+    version integer not null,
+    published_at timestamptz not null,
+    -- When members who hadn't agreed to this version were reported to moderators.
+    reported_at timestamptz,
+    primary key (guild_id, version)
+);
+
+create table onboarding_rules_acceptances (
+    guild_id bigint not null,
+    user_id bigint not null,
+    version integer not null,
+    accepted_at timestamptz not null,
+    primary key (guild_id, user_id, version)
+);
@@ -0,0 +1,2 @@
+-- Whether channel_id is a private thread created for the member, instead of the quarantine channel.
+alter table onboarding_welcome_messages add column thread boolean not null default false;
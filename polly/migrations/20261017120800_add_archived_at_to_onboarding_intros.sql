@@ -0,0 +1,2 @@
+-- Set when the member left and their intro message was deleted.
+alter table onboarding_intros add column archived_at timestamptz;
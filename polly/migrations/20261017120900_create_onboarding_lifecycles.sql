@@ -0,0 +1,15 @@
+-- One row per join, so that members who leave and rejoin are counted again.
+create table onboarding_lifecycles (
+    id bigserial primary key,
+    guild_id bigint not null,
+    user_id bigint not null,
+    joined_at timestamptz not null,
+    welcomed_at timestamptz,
+    intro_submitted_at timestamptz,
+    unquarantined_at timestamptz,
+    kicked_at timestamptz,
+    left_at timestamptz
+);
+
+create index onboarding_lifecycles_member_idx on onboarding_lifecycles (guild_id, user_id, joined_at);
+create index onboarding_lifecycles_joined_at_idx on onboarding_lifecycles (guild_id, joined_at);
@@ -0,0 +1,10 @@
+create table onboarding_lockdowns (
+    id bigserial primary key,
+    guild_id bigint not null,
+    started_at timestamptz not null,
+    -- Set when a moderator ends the lockdown.
+    ended_at timestamptz
+);
+
+-- A guild can only be in one lockdown at a time.
+create unique index onboarding_lockdowns_active_idx on onboarding_lockdowns (guild_id) where ended_at is null;
@@ -0,0 +1,3 @@
+-- Members can be quarantined long after they joined, so their deadline counts from the welcome.
+-- Welcome messages sent before this column existed count from joining.
+alter table onboarding_welcome_messages add column sent_at timestamptz;
@@ -0,0 +1,31 @@
+create type onboarding_state as enum (
+    'joined',
+    'welcomed',
+    'intro_submitted',
+    'under_review',
+    'member',
+    'kicked',
+    'left'
+);
+
+-- The current state of each member. Members who joined before states were recorded have no row.
+create table onboarding_states (
+    guild_id bigint not null,
+    user_id bigint not null,
+    state onboarding_state not null,
+    updated_at timestamptz not null,
+    primary key (guild_id, user_id)
+);
+
+-- Every change of state, so that moderators can see how a member got to theirs.
+create table onboarding_state_transitions (
+    id bigserial primary key,
+    guild_id bigint not null,
+    user_id bigint not null,
+    -- Null for the first state recorded for the member.
+    from_state onboarding_state,
+    to_state onboarding_state not null,
+    at timestamptz not null
+);
+
+create index onboarding_state_transitions_member_idx on onboarding_state_transitions (guild_id, user_id, at);
@@ -0,0 +1,8 @@
+create extension if not exists pg_trgm;
+
+-- The answers of the intro, so that intros are compared regardless of the questions.
+create function onboarding_intro_text(fields jsonb) returns text
+language sql immutable parallel safe
+as $$ select coalesce(string_agg(value, E'\n' order by key), '') from jsonb_each_text(fields) $$;
+
+create index on onboarding_intros using gin (onboarding_intro_text(fields) gin_trgm_ops);
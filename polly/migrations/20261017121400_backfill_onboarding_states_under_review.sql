@@ -0,0 +1,10 @@
+-- Members whose intro was pending review before states were recorded are under review,
+-- so that they aren't reminded or kicked while waiting on a moderator.
+with backfilled as (
+    insert into onboarding_states (guild_id, user_id, state, updated_at)
+    select guild_id, user_id, 'under_review', submitted_at from onboarding_intro_reviews where not changes_requested
+    on conflict (guild_id, user_id) do nothing
+    returning guild_id, user_id, state, updated_at
+)
+insert into onboarding_state_transitions (guild_id, user_id, to_state, at)
+select guild_id, user_id, state, updated_at from backfilled;
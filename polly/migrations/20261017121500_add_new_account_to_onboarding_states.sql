@@ -0,0 +1,3 @@
+-- Whether the member's account was too new when they were welcomed, which holds their intro for review.
+-- Members welcomed before this column existed fall back to the current age of their account.
+alter table onboarding_states add column new_account boolean;
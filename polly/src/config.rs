@@ -1,9 +1,30 @@
-use std::{collections::BTreeMap, time::Duration};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    fs,
+    time::Duration,
+};
 
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use serde::{Deserialize as _, de};
 use serde_derive::Deserialize;
-use serenity::all::{ChannelId, GuildId, RoleId};
+use serenity::all::{
+    ChannelId,
+    GuildId,
+    InputTextStyle,
+    MESSAGE_CODE_LIMIT,
+    Mentionable,
+    RoleId,
+    UserId,
+};
+
+use crate::template::Template;
+
+/// The placeholders available in welcome message templates.
+pub const WELCOME_PLACEHOLDERS: &[&str] = &["guild_name", "member", "coc_url", "rules"];
+
+/// The placeholders available in rules templates.
+pub const RULES_PLACEHOLDERS: &[&str] = &["coc_url"];
 
 #[derive(Debug, Deserialize)]
 pub struct Config {
@@ -16,9 +37,157 @@ pub struct Config {
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Deserialize)]
 pub struct GuildConfig {
+    /// Used for messages that aren't addressed to a single user, and when a user's locale isn't supported.
+    pub locale: String,
     pub quarantine_role: RoleId,
     pub quarantine_channel: ChannelId,
+    /// If set, each quarantined member is welcomed in their own private thread in the quarantine channel.
+    #[serde(default)]
+    pub welcome_threads: bool,
     pub intros_channel: ChannelId,
+    /// If set, the intros of members who leave are removed from the intros channel,
+    /// and they can restore them when they rejoin.
+    #[serde(default)]
+    pub archive_intros: bool,
+    /// If set, intros from quarantined members must be approved by a moderator in this channel.
+    pub review_channel: Option<ChannelId>,
+    /// Receives reports for moderators. Without it, reports are only logged.
+    pub mod_channel: Option<ChannelId>,
+    /// If set, moderator commands that change a member's onboarding also require this role,
+    /// in addition to the Moderate Members permission.
+    pub mod_role: Option<RoleId>,
+    /// How long after joining quarantined members are kicked.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub onboarding_deadline: Duration,
+    /// How long after joining quarantined members are reminded to introduce themselves.
+    #[serde(default, deserialize_with = "deserialize_durations")]
+    pub onboarding_reminders: Vec<Duration>,
+    #[serde(deserialize_with = "deserialize_intro_questions")]
+    pub intro_questions: Vec<IntroQuestion>,
+    /// Path to the template of the message that welcomes quarantined members.
+    #[serde(deserialize_with = "deserialize_welcome_template")]
+    pub welcome_template: Template,
+    pub coc_url: String,
+    pub rules: RulesConfig,
+    /// Catches low-effort and spam intros before they're published. Without it, intros aren't checked.
+    pub intro_check: Option<IntroCheckConfig>,
+    /// Detects join raids and locks onboarding down. Without it, join rates aren't watched.
+    pub lockdown: Option<LockdownConfig>,
+    /// Holds back recently created accounts. Without it, all accounts are treated the same.
+    pub new_accounts: Option<NewAccountsConfig>,
+    /// What happens when someone else deletes a member's intro or welcome message.
+    #[serde(default)]
+    pub deleted_messages: DeletedMessagesAction,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RulesConfig {
+    /// Bump to ask every member to agree to the rules again.
+    pub version: i32,
+    /// Path to the template of the rules.
+    #[serde(deserialize_with = "deserialize_rules_template")]
+    pub template: Template,
+    /// How long after a new version is published members who haven't agreed to it are reported.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub reaccept_deadline: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IntroCheckConfig {
+    pub action: IntroCheckAction,
+    /// Share of characters in repeated patterns, like "aaaa" or "asdfasdf", above which intros are caught.
+    #[serde(
+        default = "default_max_repeated_ratio",
+        deserialize_with = "deserialize_ratio"
+    )]
+    pub max_repeated_ratio: f64,
+    /// Whether links are allowed. Discord invites never are.
+    #[serde(default)]
+    pub allow_links: bool,
+    /// Trigram similarity to another member's intro, from 0 to 1, above which intros are caught.
+    #[serde(
+        default = "default_max_similarity",
+        deserialize_with = "deserialize_ratio"
+    )]
+    pub max_similarity: f64,
+    /// Words that intros are caught for, regardless of case.
+    #[serde(default, deserialize_with = "deserialize_lowercase")]
+    pub blocked_words: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IntroCheckAction {
+    /// Asks the member to improve their intro.
+    Reject,
+    /// Lets the intro through, and reports it to the moderators.
+    Flag,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LockdownConfig {
+    /// Number of joins within `window` that starts a lockdown.
+    pub max_joins: u32,
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub window: Duration,
+    pub action: LockdownAction,
+}
+
+/// What happens to quarantined members during a lockdown, besides alerting the moderators.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LockdownAction {
+    /// Nothing.
+    Alert,
+    /// They can't introduce themselves, and aren't kicked for it.
+    /// Once it ends, they get the full onboarding deadline again.
+    PauseIntros,
+    /// Their intros must be approved by a moderator,
+    /// in the review channel or, without one, in the mod channel.
+    HoldForReview,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletedMessagesAction {
+    /// The message is posted again.
+    #[default]
+    Repost,
+    /// The member is quarantined again, if they aren't, and the moderators are told.
+    Requarantine,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewAccountsConfig {
+    /// Accounts younger than this are new. Their intros must be approved by a moderator,
+    /// in the review channel or, without one, in the mod channel.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub min_age: Duration,
+    /// Path to the template of the message that welcomes new accounts, instead of `welcome_template`.
+    #[serde(deserialize_with = "deserialize_welcome_template")]
+    pub welcome_template: Template,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IntroQuestion {
+    pub id: String,
+    pub label: String,
+    pub placeholder: Option<String>,
+    #[serde(default)]
+    pub style: IntroQuestionStyle,
+    #[serde(default = "default_required")]
+    pub required: bool,
+    pub min_length: Option<u16>,
+    #[serde(default = "default_max_length")]
+    pub max_length: u16,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntroQuestionStyle {
+    Short,
+    #[default]
+    Paragraph,
 }
 
 #[allow(clippy::module_name_repetitions)]
@@ -29,6 +198,88 @@ pub struct AutoDeleteConfig {
     pub after: Duration,
 }
 
+impl From<IntroQuestionStyle> for InputTextStyle {
+    fn from(style: IntroQuestionStyle) -> Self {
+        match style {
+            IntroQuestionStyle::Short => InputTextStyle::Short,
+            IntroQuestionStyle::Paragraph => InputTextStyle::Paragraph,
+        }
+    }
+}
+
+impl GuildConfig {
+    /// Locales for messages addressed to the whole guild.
+    pub fn guild_locales(&self) -> [&str; 1] {
+        [&self.locale]
+    }
+
+    /// Locales for replies to a single user, falling back to the guild's locale.
+    pub fn user_locales<'a>(&'a self, user_locale: &'a str) -> [&'a str; 2] {
+        [user_locale, &self.locale]
+    }
+
+    /// Checks what depends on more than one field.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        // Otherwise, intros that must be approved would be published right away.
+        if self.review_channel.is_none() && self.mod_channel.is_none() {
+            if self.new_accounts.is_some() {
+                bail!("new_accounts requires review_channel or mod_channel");
+            }
+            if self.lockdown.as_ref().is_some_and(|lockdown_config| {
+                lockdown_config.action == LockdownAction::HoldForReview
+            }) {
+                bail!(
+                    "lockdown.action = \"hold_for_review\" requires review_channel or mod_channel"
+                );
+            }
+        }
+
+        // Reminders are sorted, and any at or after the deadline would never be sent.
+        if self
+            .onboarding_reminders
+            .last()
+            .is_some_and(|&reminder| reminder >= self.onboarding_deadline)
+        {
+            bail!("onboarding_reminders must be before onboarding_deadline");
+        }
+
+        let rules = self.rules.template.render(&[("coc_url", &self.coc_url)]);
+        check_rendered_len("rules template", &rules)?;
+
+        // The longest values that the placeholders can take.
+        let guild_name = "x".repeat(GUILD_NAME_LIMIT);
+        let member = UserId::new(u64::MAX).mention();
+        let values: &[(&str, &dyn fmt::Display)] = &[
+            ("guild_name", &guild_name),
+            ("member", &member),
+            ("coc_url", &self.coc_url),
+            ("rules", &rules),
+        ];
+
+        check_rendered_len("welcome template", &self.welcome_template.render(values))?;
+        if let Some(new_accounts) = &self.new_accounts {
+            check_rendered_len(
+                "new accounts welcome template",
+                &new_accounts.welcome_template.render(values),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// Discord's limit on guild names.
+const GUILD_NAME_LIMIT: usize = 100;
+
+fn check_rendered_len(name: &str, rendered: &str) -> anyhow::Result<()> {
+    let len = rendered.chars().count();
+    if len > MESSAGE_CODE_LIMIT {
+        bail!("{name} can be {len} characters long once rendered, more than {MESSAGE_CODE_LIMIT}");
+    }
+
+    Ok(())
+}
+
 impl Config {
     pub fn guild(&self, id: GuildId) -> crate::error::Result<&GuildConfig> {
         self.guilds
@@ -60,6 +311,139 @@ where
     Ok(parsed_map)
 }
 
+fn default_required() -> bool {
+    true
+}
+
+// Intros are rendered as embed fields, which can hold at most 1024 characters.
+const EMBED_FIELD_VALUE_LIMIT: u16 = 1024;
+
+fn default_max_length() -> u16 {
+    EMBED_FIELD_VALUE_LIMIT
+}
+
+fn deserialize_intro_questions<'de, D>(deserializer: D) -> Result<Vec<IntroQuestion>, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    // Limits imposed by Discord on modals and text inputs.
+    const MAX_QUESTIONS: usize = 5;
+    const MAX_LABEL_LEN: usize = 45;
+    const MAX_PLACEHOLDER_LEN: usize = 100;
+
+    let questions = Vec::<IntroQuestion>::deserialize(deserializer)?;
+
+    if questions.is_empty() || questions.len() > MAX_QUESTIONS {
+        return Err(de::Error::invalid_length(
+            questions.len(),
+            &"between 1 and 5 intro questions",
+        ));
+    }
+
+    let mut ids = HashSet::new();
+    for question in &questions {
+        if question.id.is_empty() || !ids.insert(question.id.as_str()) {
+            return Err(de::Error::custom(format_args!(
+                "intro question IDs must be unique and non-empty: {:?}",
+                question.id,
+            )));
+        }
+
+        if question.label.is_empty() || question.label.chars().count() > MAX_LABEL_LEN {
+            return Err(de::Error::custom(format_args!(
+                "intro question label must be between 1 and {MAX_LABEL_LEN} characters: {:?}",
+                question.label,
+            )));
+        }
+
+        if question
+            .placeholder
+            .as_ref()
+            .is_some_and(|placeholder| placeholder.chars().count() > MAX_PLACEHOLDER_LEN)
+        {
+            return Err(de::Error::custom(format_args!(
+                "intro question placeholder must be at most {MAX_PLACEHOLDER_LEN} characters: {:?}",
+                question.id,
+            )));
+        }
+
+        if question.max_length == 0
+            || question.max_length > EMBED_FIELD_VALUE_LIMIT
+            || question
+                .min_length
+                .is_some_and(|min_length| min_length > question.max_length)
+        {
+            return Err(de::Error::custom(format_args!(
+                "intro question lengths must satisfy min_length <= max_length <= {EMBED_FIELD_VALUE_LIMIT}: {:?}",
+                question.id,
+            )));
+        }
+    }
+
+    Ok(questions)
+}
+
+fn default_max_repeated_ratio() -> f64 {
+    0.3
+}
+
+fn default_max_similarity() -> f64 {
+    0.8
+}
+
+fn deserialize_ratio<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    let ratio = f64::deserialize(deserializer)?;
+
+    if !(0.0..=1.0).contains(&ratio) {
+        return Err(de::Error::invalid_value(
+            de::Unexpected::Float(ratio),
+            &"a number between 0 and 1",
+        ));
+    }
+
+    Ok(ratio)
+}
+
+fn deserialize_lowercase<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    let strs = Vec::<String>::deserialize(deserializer)?;
+
+    Ok(strs.iter().map(|s| s.to_lowercase()).collect())
+}
+
+fn deserialize_welcome_template<'de, D>(deserializer: D) -> Result<Template, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    let path = String::deserialize(deserializer)?;
+    load_template(&path, WELCOME_PLACEHOLDERS)
+}
+
+fn deserialize_rules_template<'de, D>(deserializer: D) -> Result<Template, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    let path = String::deserialize(deserializer)?;
+    load_template(&path, RULES_PLACEHOLDERS)
+}
+
+fn load_template<E: de::Error>(
+    path: &str,
+    placeholders: &'static [&'static str],
+) -> Result<Template, E> {
+    let source =
+        fs::read_to_string(path).map_err(|err| E::custom(format_args!("reading {path}: {err}")))?;
+    let source = source.trim_end();
+
+    Template::parse(source, placeholders)
+        .map_err(|err| E::custom(format_args!("parsing {path}: {err}")))
+}
+
 fn deserialize_duration<'de, D>(deserializer: D) -> Result<std::time::Duration, D::Error>
 where
     D: de::Deserializer<'de>,
@@ -70,3 +454,22 @@ where
 
     Ok(iso_duration.into())
 }
+
+fn deserialize_durations<'de, D>(deserializer: D) -> Result<Vec<std::time::Duration>, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    let strs = Vec::<String>::deserialize(deserializer)?;
+
+    let mut durations = strs
+        .iter()
+        .map(|s| {
+            iso8601::duration(s)
+                .map(Into::into)
+                .map_err(de::Error::custom)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    durations.sort();
+
+    Ok(durations)
+}
@@ -1,11 +1,22 @@
 use sqlx::PgPool;
 
-use crate::{Data, PoiseApplicationContext, PoiseContext, PoiseFrameworkContext, config::Config};
+use crate::{
+    Data,
+    PoiseApplicationContext,
+    PoiseContext,
+    PoiseFrameworkContext,
+    config::Config,
+    l10n::Catalog,
+};
 
 pub trait Context {
     fn serenity(&self) -> &serenity::client::Context;
     fn data(&self) -> &Data;
 
+    fn catalog(&self) -> &Catalog {
+        &self.data().catalog
+    }
+
     fn config(&self) -> &Config {
         &self.data().config
     }
@@ -0,0 +1,107 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{Context as _, anyhow};
+use fluent_bundle::{FluentArgs, FluentResource, concurrent::FluentBundle};
+use unic_langid::LanguageIdentifier;
+
+/// Used when none of the requested locales have the message.
+pub const FALLBACK_LOCALE: &str = "en";
+
+/// Messages for every supported locale, loaded from `<locale>.ftl` files.
+pub struct Catalog {
+    bundles: HashMap<String, FluentBundle<FluentResource>>,
+}
+
+impl Catalog {
+    pub fn load(dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let mut bundles = HashMap::new();
+
+        for entry in fs::read_dir(dir.as_ref()).context("Reading catalog directory")? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|extension| extension != "ftl") {
+                continue;
+            }
+
+            let locale = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .with_context(|| format!("Invalid catalog file name: {}", path.display()))?;
+            let langid: LanguageIdentifier = locale
+                .parse()
+                .with_context(|| format!("Invalid locale: {locale}"))?;
+
+            let source =
+                fs::read_to_string(&path).with_context(|| format!("Reading {}", path.display()))?;
+            let resource = FluentResource::try_new(source)
+                .map_err(|(_, errors)| anyhow!("Parsing {}: {errors:?}", path.display()))?;
+
+            let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+            // Isolation marks would end up in messages, and break mentions and links.
+            bundle.set_use_isolating(false);
+            bundle
+                .add_resource(resource)
+                .map_err(|errors| anyhow!("Loading {}: {errors:?}", path.display()))?;
+
+            bundles.insert(locale.to_owned(), bundle);
+        }
+
+        if !bundles.contains_key(FALLBACK_LOCALE) {
+            anyhow::bail!("Missing catalog for fallback locale {FALLBACK_LOCALE}");
+        }
+
+        Ok(Catalog { bundles })
+    }
+
+    /// Finds the catalog for the locale, or for its language if there is no regional catalog.
+    fn bundle(&self, locale: &str) -> Option<&FluentBundle<FluentResource>> {
+        self.bundles.get(locale).or_else(|| {
+            let (language, _region) = locale.split_once('-')?;
+            self.bundles.get(language)
+        })
+    }
+
+    pub fn has_locale(&self, locale: &str) -> bool {
+        self.bundle(locale).is_some()
+    }
+
+    /// Formats the message using the first of the locales that has it.
+    pub fn format(&self, locales: &[&str], id: &str, args: &FluentArgs) -> String {
+        let bundles = locales
+            .iter()
+            .chain([&FALLBACK_LOCALE])
+            .filter_map(|locale| self.bundle(locale));
+
+        for bundle in bundles {
+            let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) else {
+                continue;
+            };
+
+            let mut errors = Vec::new();
+            let formatted = bundle.format_pattern(pattern, Some(args), &mut errors);
+            if !errors.is_empty() {
+                tracing::warn!(id, ?errors, "Errors while formatting message");
+            }
+
+            return formatted.into_owned();
+        }
+
+        tracing::error!(id, "Unknown message");
+        id.to_owned()
+    }
+}
+
+/// Formats a message from the catalog, in the first of the locales that has it.
+///
+/// ```ignore
+/// tr!(ctx, [&interaction.locale, &config.locale], "intro-updated", url = url.to_string())
+/// ```
+macro_rules! tr {
+    ($ctx:expr, $locales:expr, $id:literal $(, $arg:ident = $value:expr)* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut args = ::fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($arg), $value);)*
+
+        $crate::context::Context::catalog($ctx).format(&$locales, $id, &args)
+    }};
+}
+pub(crate) use tr;
@@ -5,8 +5,10 @@ mod context;
 mod cracker;
 mod error;
 mod error_reporting;
+mod l10n;
 mod onboarding;
 mod task;
+mod template;
 
 use std::{
     fs,
@@ -29,11 +31,13 @@ use crate::{
     config::Config,
     error::Error,
     error_reporting::{report_error, report_event_handler_error},
+    l10n::{Catalog, tr},
 };
 
 static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
 
 pub struct DataInner {
+    pub catalog: Catalog,
     pub config: Config,
     pub db: PgPool,
     pub tenor_api_key: String,
@@ -52,7 +56,10 @@ fn commands() -> Vec<PoiseCommand> {
     vec![
         bubblewrap(),
         onboarding::intro(),
+        onboarding::mydata(),
+        onboarding::onboarding(),
         onboarding::onboarding_sync_db(),
+        onboarding::view_intro(),
     ]
 }
 
@@ -61,6 +68,7 @@ async fn setup(
     serenity_context: &serenity::client::Context,
     ready: &Ready,
     framework: &PoiseFramework,
+    catalog: Catalog,
     config: Config,
     db: PgPool,
     tenor_api_key: String,
@@ -75,6 +83,7 @@ async fn setup(
     }
 
     let data = Arc::new(DataInner {
+        catalog,
         config,
         db,
         tenor_api_key,
@@ -103,19 +112,26 @@ async fn setup(
 
     spawn_periodic!(auto_delete, 1 m);
     spawn_periodic!(onboarding::check_quarantine, 10 m);
+    spawn_periodic!(onboarding::check_rules, 1 h);
     spawn_periodic!(onboarding::kick_inactive, 1 h);
+    spawn_periodic!(onboarding::send_reminders, 10 m);
 
     Ok(data)
 }
 
 async fn on_error(err: PoiseFrameworkError<'_>) {
-    const ERROR_REPLY_TEXT: &str = "😵‍💫 Something went wrong. I'll let my admins know about it.";
-
     async fn inner(err: PoiseFrameworkError<'_>) -> crate::error::Result<()> {
         match err {
             poise::FrameworkError::Command { ctx, .. }
             | poise::FrameworkError::CommandPanic { ctx, .. } => {
-                ctx.say(ERROR_REPLY_TEXT).await?;
+                let guild_locale = ctx
+                    .guild_id()
+                    .and_then(|guild_id| ctx.data().config.guilds.get(&guild_id))
+                    .map_or(l10n::FALLBACK_LOCALE, |config| config.locale.as_str());
+                let user_locale = ctx.locale().unwrap_or(guild_locale);
+
+                ctx.say(tr!(&ctx, [user_locale, guild_locale], "error-reply"))
+                    .await?;
                 report_error(err).await?;
             }
 
@@ -166,6 +182,7 @@ async fn handle_event(
 
 async fn serenity_client(
     token: String,
+    catalog: Catalog,
     config: Config,
     db: PgPool,
     tenor_api_key: String,
@@ -180,6 +197,7 @@ async fn serenity_client(
                 serenity_context,
                 ready,
                 framework,
+                catalog,
                 config,
                 db,
                 tenor_api_key,
@@ -231,12 +249,28 @@ async fn shuttle_main(
         toml::from_str(&fs::read_to_string("polly.toml").context("Reading polly.toml")?)
             .context("Parsing config")?;
 
+    let catalog = Catalog::load("locales").context("Loading message catalog")?;
+
+    for (guild_id, guild_config) in &config.guilds {
+        if !catalog.has_locale(&guild_config.locale) {
+            return Err(anyhow::anyhow!(
+                "No catalog for locale {} of guild {guild_id}",
+                guild_config.locale,
+            )
+            .into());
+        }
+
+        guild_config
+            .validate()
+            .with_context(|| format!("Invalid config of guild {guild_id}"))?;
+    }
+
     sqlx::migrate!()
         .run(&db)
         .await
         .context("Migrating database")?;
 
-    let client = serenity_client(token, config, db, tenor_api_key)
+    let client = serenity_client(token, catalog, config, db, tenor_api_key)
         .await
         .context("Creating framework")?;
 
@@ -0,0 +1,140 @@
+use anyhow::Context as _;
+use serenity::all::{
+    ButtonStyle,
+    ComponentInteraction,
+    CreateActionRow,
+    CreateButton,
+    CreateInteractionResponse,
+    CreateInteractionResponseMessage,
+    GuildId,
+    User,
+    UserId,
+};
+use tracing::info;
+
+use super::{create_onboarding_prompt, intro, persist};
+use crate::{
+    PoiseApplicationContext,
+    config::GuildConfig,
+    context::Context,
+    error::Result,
+    l10n::tr,
+};
+
+pub const CONFIRM_ID: &str = "onboarding_confirm_age";
+
+pub fn create_button(ctx: &impl Context, locales: &[&str]) -> CreateButton {
+    CreateButton::new(CONFIRM_ID)
+        .label(tr!(ctx, locales, "age-button"))
+        .emoji('🔞')
+        .style(ButtonStyle::Secondary)
+}
+
+pub async fn is_confirmed(ctx: &impl Context, guild_id: GuildId, user_id: UserId) -> Result<bool> {
+    let confirmed = persist::age_confirmation::get(ctx.db(), guild_id, user_id)
+        .await?
+        .is_some();
+
+    Ok(confirmed)
+}
+
+/// Asks the member to confirm their age before introducing themselves.
+pub fn create_prompt(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    user_locale: &str,
+) -> CreateInteractionResponseMessage {
+    let locales = config.user_locales(user_locale);
+
+    CreateInteractionResponseMessage::new()
+        .content(tr!(ctx, locales, "age-required"))
+        .components(vec![CreateActionRow::Buttons(vec![create_button(
+            ctx, &locales,
+        )])])
+        .ephemeral(true)
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn component_interaction(
+    ctx: &impl Context,
+    interaction: &ComponentInteraction,
+) -> Result<()> {
+    let guild_id = interaction
+        .guild_id
+        .context("Interaction has no guild_id")?;
+    let config = ctx.config().guild(guild_id)?;
+
+    persist::age_confirmation::set(ctx.db(), guild_id, interaction.user.id, chrono::Utc::now())
+        .await?;
+
+    info!(%guild_id, %interaction.user.id, "Member confirmed their age");
+
+    let prompt = create_onboarding_prompt(
+        ctx,
+        config,
+        guild_id,
+        interaction.user.id,
+        &interaction.locale,
+    )
+    .await?;
+
+    let response = prompt.unwrap_or_else(|| {
+        CreateInteractionResponseMessage::new()
+            .content(tr!(
+                ctx,
+                config.user_locales(&interaction.locale),
+                "age-confirmed",
+            ))
+            .components(vec![CreateActionRow::Buttons(vec![intro::create_button(
+                ctx, config,
+            )])])
+            .ephemeral(true)
+    });
+
+    interaction
+        .create_response(ctx.serenity(), CreateInteractionResponse::Message(response))
+        .await?;
+
+    Ok(())
+}
+
+/// Show when a member confirmed being 18 or older
+#[poise::command(guild_only, slash_command)]
+#[tracing::instrument(
+    fields(
+        ctx.id = ctx.id(),
+        ctx.guild_id = %ctx.guild_id().unwrap_or_default(),
+        ctx.author.id = %ctx.author().id,
+        %user.id,
+    ),
+    skip(ctx, user),
+)]
+pub async fn age(
+    ctx: PoiseApplicationContext<'_>,
+    #[description = "Member whose confirmation to show"] user: User,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx.guild_id().context("Context has no guild_id")?;
+    let config = ctx.config().guild(guild_id)?;
+    let locales = config.user_locales(&ctx.interaction.locale);
+
+    let content = persist::age_confirmation::get(ctx.db(), guild_id, user.id)
+        .await?
+        .map_or_else(
+            || tr!(&ctx, locales, "age-lookup-none", user = user.to_string()),
+            |confirmed_at| {
+                tr!(
+                    &ctx,
+                    locales,
+                    "age-lookup",
+                    user = user.to_string(),
+                    confirmed_at = format!("<t:{}:f>", confirmed_at.timestamp()),
+                )
+            },
+        );
+
+    ctx.say(content).await?;
+
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use serenity::all::{ChannelId, GuildId, Member, MessageId, UserId};
+use tracing::info;
+
+use super::{
+    intro,
+    persist,
+    quarantine::{quarantine, send_welcome_message, set_welcome_awaiting_review},
+    report,
+    review,
+};
+use crate::{
+    config::{DeletedMessagesAction, GuildConfig},
+    context::Context,
+    error::{Result, is_http_not_found},
+    l10n::tr,
+};
+
+/// The member, unless they left the guild.
+async fn get_member(
+    ctx: &impl Context,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<Option<Member>> {
+    match guild_id.member(ctx.serenity(), user_id).await {
+        Ok(member) => Ok(Some(member)),
+        Err(err) if is_http_not_found(&err) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Whether the channel is a thread in the quarantine channel, like welcome threads.
+/// Checked in the cache, so that messages deleted anywhere else don't query the database.
+fn is_quarantine_thread(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> bool {
+    ctx.serenity().cache.guild(guild_id).is_some_and(|guild| {
+        guild.threads.iter().any(|thread| {
+            thread.id == channel_id && thread.parent_id == Some(config.quarantine_channel)
+        })
+    })
+}
+
+/// Cleans up after intro and welcome messages that someone else deleted.
+/// Messages deleted by Polly itself have no rows left by the time the event arrives.
+#[tracing::instrument(skip(ctx))]
+pub async fn messages_deleted(
+    ctx: &impl Context,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    message_ids: &[MessageId],
+) -> Result<()> {
+    let Some(config) = ctx.config().guilds.get(&guild_id) else {
+        return Ok(());
+    };
+
+    if channel_id == config.intros_channel {
+        let user_ids =
+            persist::intro_message::delete_by_messages(ctx.db(), guild_id, message_ids).await?;
+        for user_id in user_ids {
+            intro_deleted(ctx, config, guild_id, user_id).await?;
+        }
+    } else if channel_id == config.quarantine_channel
+        || (config.welcome_threads && is_quarantine_thread(ctx, config, guild_id, channel_id))
+    {
+        // Welcome threads belong to a single member, and their welcome message is reposted there.
+        let thread = (channel_id != config.quarantine_channel).then_some(channel_id);
+
+        let user_ids =
+            persist::welcome_message::delete_by_messages(ctx.db(), guild_id, message_ids).await?;
+        for user_id in user_ids {
+            welcome_deleted(ctx, config, guild_id, user_id, thread).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(ctx, config))]
+async fn intro_deleted(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<()> {
+    info!("Intro message was deleted");
+
+    // The intro stays in the database, to be restored if they rejoin.
+    let Some(member) = get_member(ctx, guild_id, user_id).await? else {
+        return Ok(());
+    };
+
+    match config.deleted_messages {
+        DeletedMessagesAction::Repost => {
+            if let Some(intro) = intro::get(ctx, guild_id, user_id).await? {
+                intro::publish(ctx, &member, &intro).await?;
+            }
+        }
+        DeletedMessagesAction::Requarantine => {
+            if !member.roles.contains(&config.quarantine_role) {
+                quarantine(ctx, &member).await?;
+            }
+
+            let text = tr!(
+                ctx,
+                config.guild_locales(),
+                "deleted-intro-report",
+                member = member.to_string(),
+                tag = member.user.tag(),
+            );
+            report(ctx, config, text, "deleted_intro.md").await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(ctx, config))]
+async fn welcome_deleted(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_id: GuildId,
+    user_id: UserId,
+    thread: Option<ChannelId>,
+) -> Result<()> {
+    info!("Welcome message was deleted");
+
+    let Some(member) = get_member(ctx, guild_id, user_id).await? else {
+        return Ok(());
+    };
+
+    match config.deleted_messages {
+        DeletedMessagesAction::Repost => {
+            if !member.roles.contains(&config.quarantine_role) {
+                return Ok(());
+            }
+
+            send_welcome_message(ctx, &member, thread).await?;
+        }
+        DeletedMessagesAction::Requarantine => {
+            quarantine(ctx, &member).await?;
+
+            let text = tr!(
+                ctx,
+                config.guild_locales(),
+                "deleted-welcome-report",
+                member = member.to_string(),
+                tag = member.user.tag(),
+            );
+            report(ctx, config, text, "deleted_welcome.md").await?;
+        }
+    }
+
+    if review::is_pending(ctx, guild_id, user_id).await? {
+        set_welcome_awaiting_review(ctx, guild_id, user_id, true).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use anyhow::Context as _;
+use serenity::all::User;
+use similar::{ChangeTag, TextDiff};
+
+use super::{persist, reply_long};
+use crate::{
+    PoiseApplicationContext,
+    config::GuildConfig,
+    context::Context,
+    error::Result,
+    l10n::tr,
+};
+
+fn write_diff(
+    ctx: &impl Context,
+    locales: &[&str],
+    w: &mut impl Write,
+    config: &GuildConfig,
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+) -> Result<()> {
+    let mut ids: Vec<&str> = config
+        .intro_questions
+        .iter()
+        .map(|question| question.id.as_str())
+        .collect();
+
+    // Include answers to questions that have since been removed from the config.
+    for id in old.keys().chain(new.keys()) {
+        if !ids.contains(&id.as_str()) {
+            ids.push(id);
+        }
+    }
+
+    let mut n_changed = 0;
+
+    for id in ids {
+        let old_value = old.get(id).map_or("", String::as_str);
+        let new_value = new.get(id).map_or("", String::as_str);
+
+        if old_value == new_value {
+            continue;
+        }
+
+        n_changed += 1;
+
+        let label = config
+            .intro_questions
+            .iter()
+            .find(|question| question.id == id)
+            .map_or(id, |question| question.label.as_str());
+
+        writeln!(w, "__{label}__")?;
+        writeln!(w, "```diff")?;
+        for change in TextDiff::from_lines(old_value, new_value).iter_all_changes() {
+            let sign = match change.tag() {
+                ChangeTag::Delete => '-',
+                ChangeTag::Insert => '+',
+                ChangeTag::Equal => ' ',
+            };
+            write!(w, "{sign} {}", change.value())?;
+            if change.missing_newline() {
+                writeln!(w)?;
+            }
+        }
+        writeln!(w, "```")?;
+    }
+
+    if n_changed == 0 {
+        writeln!(w, "{}", tr!(ctx, locales, "history-no-changes"))?;
+    }
+
+    Ok(())
+}
+
+/// Show the revisions of a member's introduction
+#[poise::command(guild_only, slash_command)]
+#[tracing::instrument(
+    fields(
+        ctx.id = ctx.id(),
+        ctx.guild_id = %ctx.guild_id().unwrap_or_default(),
+        ctx.author.id = %ctx.author().id,
+        %user.id,
+    ),
+    skip(ctx, user),
+)]
+pub async fn history(
+    ctx: PoiseApplicationContext<'_>,
+    #[description = "Member whose introduction to show"] user: User,
+    #[description = "Revision to compare from (default: the one before `to`)"]
+    #[min = 1]
+    from: Option<usize>,
+    #[description = "Revision to compare to (default: the latest)"]
+    #[min = 1]
+    to: Option<usize>,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx.guild_id().context("Context has no guild_id")?;
+    let config = ctx.config().guild(guild_id)?;
+    let locales = config.user_locales(&ctx.interaction.locale);
+
+    let revisions = persist::intro_revision::get_all(ctx.db(), guild_id, user.id).await?;
+
+    if revisions.is_empty() {
+        ctx.say(tr!(&ctx, locales, "history-none", user = user.to_string()))
+            .await?;
+        return Ok(());
+    }
+
+    let to = to.unwrap_or(revisions.len());
+    let from = from.unwrap_or(to.saturating_sub(1));
+
+    if from > revisions.len() || to > revisions.len() {
+        ctx.say(tr!(
+            &ctx,
+            locales,
+            "history-out-of-range",
+            user = user.to_string(),
+            count = revisions.len(),
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let mut text = String::new();
+
+    writeln!(
+        text,
+        "{}",
+        tr!(&ctx, locales, "history-title", user = user.to_string()),
+    )?;
+    for (i, revision) in revisions.iter().enumerate() {
+        writeln!(text, "{}. <t:{}:f>", i + 1, revision.created_at.timestamp())?;
+    }
+
+    // `from` is 0 when the only revision is the first one.
+    if from > 0 && from != to {
+        writeln!(text)?;
+        writeln!(
+            text,
+            "{}",
+            tr!(&ctx, locales, "history-changes-title", from = from, to = to),
+        )?;
+        write_diff(
+            &ctx,
+            &locales,
+            &mut text,
+            config,
+            &revisions[from - 1].fields,
+            &revisions[to - 1].fields,
+        )?;
+    }
+
+    reply_long(ctx, text, "intro_history.md").await
+}
@@ -1,6 +1,11 @@
+use std::collections::BTreeMap;
+
 use anyhow::Context as _;
 use serenity::all::{
     ActionRowComponent,
+    ButtonStyle,
+    ChannelId,
+    ComponentInteraction,
     CreateActionRow,
     CreateButton,
     CreateEmbed,
@@ -11,56 +16,75 @@ use serenity::all::{
     CreateModal,
     EditMessage,
     GuildId,
-    InputTextStyle,
     Member,
     Message,
     ModalInteraction,
     User,
     UserId,
 };
-use tracing::warn;
-
-use super::{persist, quarantine::unquarantine};
+use sqlx::PgConnection;
+use tracing::{info, warn};
+
+use super::{
+    create_onboarding_prompt,
+    intro_check,
+    lockdown,
+    new_accounts,
+    persist::{self, state::State},
+    quarantine::unquarantine,
+    review,
+    state,
+};
 use crate::{
+    config::{GuildConfig, IntroQuestion},
     context::Context,
-    error::{Result, is_http_not_found},
+    error::{Result, bail, is_http_not_found},
+    l10n::tr,
 };
 
 pub const MODAL_ID: &str = "onboarding_intro";
-const ID_ABOUT_ME: &str = "about_me";
-const ID_POLYAMORY_EXPERIENCE: &str = "polyamory_experience";
-
-const LABEL_INTRODUCE_YOURSELF: &str = "Introduce yourself";
-const LABEL_ABOUT_ME: &str = "About me";
-const LABEL_POLYAMORY_EXPERIENCE: &str = "Polyamory experience";
+pub const RESTORE_ID: &str = "onboarding_restore_intro";
 
+/// Answers to a guild's intro questions, keyed by question ID.
 pub struct Intro {
-    pub about_me: String,
-    pub polyamory_experience: String,
+    pub fields: BTreeMap<String, String>,
 }
 
 impl Intro {
-    fn from_fields<'a>(fields: impl Iterator<Item = (&'a str, impl Into<String>)>) -> Result<Self> {
-        let mut about_me: Option<String> = None;
-        let mut polyamory_experience: Option<String> = None;
+    fn from_fields<'a>(
+        questions: &[IntroQuestion],
+        fields: impl Iterator<Item = (&'a str, impl Into<String>)>,
+    ) -> Result<Self> {
+        let mut intro = Intro {
+            fields: BTreeMap::new(),
+        };
 
         for (id, value) in fields {
-            match id {
-                ID_ABOUT_ME => about_me = Some(value.into()),
-                ID_POLYAMORY_EXPERIENCE => polyamory_experience = Some(value.into()),
-                _ => warn!(id, "Unhandled field ID"),
+            if questions.iter().any(|question| question.id == id) {
+                let value: String = value.into();
+                if !value.trim().is_empty() {
+                    intro.fields.insert(id.to_owned(), value);
+                }
+            } else {
+                warn!(id, "Unhandled field ID");
             }
         }
 
-        Ok(Intro {
-            about_me: about_me.with_context(|| format!("Missing field: {ID_ABOUT_ME}"))?,
-            polyamory_experience: polyamory_experience
-                .with_context(|| format!("Missing field: {ID_POLYAMORY_EXPERIENCE}"))?,
-        })
+        for question in questions.iter().filter(|question| question.required) {
+            if intro.get(&question.id).is_none() {
+                bail!("Missing field: {}", question.id);
+            }
+        }
+
+        Ok(intro)
     }
 
-    fn from_modal_interaction(interaction: &ModalInteraction) -> Result<Self> {
+    fn from_modal_interaction(
+        questions: &[IntroQuestion],
+        interaction: &ModalInteraction,
+    ) -> Result<Self> {
         Self::from_fields(
+            questions,
             interaction
                 .data
                 .components
@@ -75,16 +99,16 @@ impl Intro {
         )
     }
 
-    fn from_message_embeds(message: Message) -> Result<Self> {
-        fn label_to_id(label: &str) -> Option<&'static str> {
-            match label {
-                LABEL_ABOUT_ME => Some(ID_ABOUT_ME),
-                LABEL_POLYAMORY_EXPERIENCE => Some(ID_POLYAMORY_EXPERIENCE),
-                _ => None,
-            }
-        }
+    pub fn from_message_embeds(questions: &[IntroQuestion], message: Message) -> Result<Self> {
+        let label_to_id = |label: &str| {
+            questions
+                .iter()
+                .find(|question| question.label == label)
+                .map(|question| question.id.as_str())
+        };
 
         Self::from_fields(
+            questions,
             message
                 .embeds
                 .into_iter()
@@ -92,85 +116,146 @@ impl Intro {
                 .filter_map(|field| label_to_id(&field.name).map(|id| (id, field.value))),
         )
     }
+
+    pub fn get(&self, id: &str) -> Option<&str> {
+        self.fields.get(id).map(String::as_str)
+    }
 }
 
-pub fn create_button() -> CreateButton {
+pub fn create_button(ctx: &impl Context, config: &GuildConfig) -> CreateButton {
     CreateButton::new(MODAL_ID)
-        .label(LABEL_INTRODUCE_YOURSELF)
+        .label(tr!(ctx, config.guild_locales(), "intro-button"))
         .emoji('👋')
 }
 
+/// The member's intro, imported from the published message if it predates storing intros in the
+/// database.
 pub async fn get(ctx: &impl Context, guild_id: GuildId, user_id: UserId) -> Result<Option<Intro>> {
-    let mut tx = ctx.db().begin().await?;
+    if let Some(fields) = persist::intro::get(ctx.db(), guild_id, user_id).await? {
+        return Ok(Some(Intro { fields }));
+    }
 
-    let message = if let Some((channel_id, message_id)) =
-        persist::intro_message::get(&mut *tx, guild_id, user_id).await?
-    {
-        channel_id
-            .message(ctx.serenity(), message_id)
-            .await
-            .map(Some)
-            .or_else(|err| {
-                if is_http_not_found(&err) {
-                    Ok(None)
-                } else {
-                    Err(err)
-                }
-            })?
-    } else {
-        None
+    import(ctx, guild_id, user_id).await
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn import(ctx: &impl Context, guild_id: GuildId, user_id: UserId) -> Result<Option<Intro>> {
+    let config = ctx.config().guild(guild_id)?;
+
+    let Some((channel_id, message_id)) =
+        persist::intro_message::get(ctx.db(), guild_id, user_id).await?
+    else {
+        return Ok(None);
     };
 
-    tx.commit().await?;
+    let message = match channel_id.message(ctx.serenity(), message_id).await {
+        Ok(message) => message,
+        Err(err) if is_http_not_found(&err) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let at = *message.timestamp;
 
-    let intro = message.map(Intro::from_message_embeds).transpose()?;
+    let intro = match Intro::from_message_embeds(&config.intro_questions, message) {
+        Ok(intro) => intro,
+        Err(err) => {
+            warn!(?err, "Couldn't import published intro");
+            return Ok(None);
+        }
+    };
+
+    persist::intro::set(ctx.db(), guild_id, user_id, &intro.fields, at).await?;
+    info!("Imported published intro");
+
+    Ok(Some(intro))
+}
+
+pub async fn get_archived(
+    ctx: &impl Context,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<Option<Intro>> {
+    let intro = persist::intro::get_archived(ctx.db(), guild_id, user_id)
+        .await?
+        .map(|fields| Intro { fields });
 
     Ok(intro)
 }
 
-fn create_modal(existing_intro: Option<&Intro>) -> CreateModal {
-    let mut about_me = CreateInputText::new(InputTextStyle::Paragraph, LABEL_ABOUT_ME, ID_ABOUT_ME)
-        .placeholder("I like long walks on the beach... 🏖")
-        .required(true)
-        .min_length(50)
-        .max_length(1000);
-    if let Some(intro) = existing_intro {
-        about_me = about_me.value(&intro.about_me);
-    }
+pub fn create_restore_button(ctx: &impl Context, config: &GuildConfig) -> CreateButton {
+    CreateButton::new(RESTORE_ID)
+        .label(tr!(ctx, config.guild_locales(), "intro-restore-button"))
+        .emoji('♻')
+        .style(ButtonStyle::Secondary)
+}
 
-    let mut polyamory_experience = CreateInputText::new(
-        InputTextStyle::Paragraph,
-        LABEL_POLYAMORY_EXPERIENCE,
-        ID_POLYAMORY_EXPERIENCE,
-    )
-    .placeholder("It's okay if you have none 💕")
-    .required(true)
-    .max_length(1000);
-    if let Some(intro) = existing_intro {
-        polyamory_experience = polyamory_experience.value(&intro.polyamory_experience);
-    }
+fn create_modal(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    user_locale: &str,
+    existing_intro: Option<&Intro>,
+) -> CreateModal {
+    let components = config
+        .intro_questions
+        .iter()
+        .map(|question| {
+            let mut input_text =
+                CreateInputText::new(question.style.into(), &question.label, &question.id)
+                    .required(question.required)
+                    .max_length(question.max_length);
+            if let Some(placeholder) = &question.placeholder {
+                input_text = input_text.placeholder(placeholder);
+            }
+            if let Some(min_length) = question.min_length {
+                input_text = input_text.min_length(min_length);
+            }
+            if let Some(value) = existing_intro.and_then(|intro| intro.get(&question.id)) {
+                input_text = input_text.value(value);
+            }
+
+            CreateActionRow::InputText(input_text)
+        })
+        .collect();
+
+    let title = tr!(ctx, config.user_locales(user_locale), "intro-modal-title");
 
-    CreateModal::new(MODAL_ID, LABEL_INTRODUCE_YOURSELF).components(vec![
-        CreateActionRow::InputText(about_me),
-        CreateActionRow::InputText(polyamory_experience),
-    ])
+    CreateModal::new(MODAL_ID, title).components(components)
 }
 
-pub async fn create_modal_for_member(ctx: &impl Context, member: &Member) -> Result<CreateModal> {
-    let existing_intro = get(ctx, member.guild_id, member.user.id).await?;
+pub async fn create_modal_for_member(
+    ctx: &impl Context,
+    member: &Member,
+    user_locale: &str,
+) -> Result<CreateModal> {
+    let config = ctx.config().guild(member.guild_id)?;
 
-    Ok(create_modal(existing_intro.as_ref()))
+    // Prefer an intro that was returned for changes, so that the member can edit it.
+    let existing_intro =
+        match persist::intro_review::get(ctx.db(), member.guild_id, member.user.id).await? {
+            Some(review) => Some(Intro {
+                fields: review.fields,
+            }),
+            None => get(ctx, member.guild_id, member.user.id).await?,
+        };
+
+    Ok(create_modal(
+        ctx,
+        config,
+        user_locale,
+        existing_intro.as_ref(),
+    ))
 }
 
-fn create_embed(user: &User, intro: &Intro) -> CreateEmbed {
+/// `name` is the member's display name in the guild, if they're a member.
+pub fn create_embed(config: &GuildConfig, user: &User, name: &str, intro: &Intro) -> CreateEmbed {
     let mut embed = CreateEmbed::new()
-        .description(format!("{user}"))
-        .field(LABEL_ABOUT_ME, &intro.about_me, false)
-        .field(
-            LABEL_POLYAMORY_EXPERIENCE,
-            &intro.polyamory_experience,
-            false,
-        );
+        .title(name)
+        .description(format!("{user}"));
+
+    for question in &config.intro_questions {
+        if let Some(value) = intro.get(&question.id) {
+            embed = embed.field(&question.label, value, false);
+        }
+    }
 
     if let Some(avatar_url) = user.static_avatar_url() {
         embed = embed.thumbnail(avatar_url);
@@ -179,22 +264,51 @@ fn create_embed(user: &User, intro: &Intro) -> CreateEmbed {
     embed
 }
 
-fn create_message(user: &User, intro: &Intro) -> CreateMessage {
+fn create_message(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    member: &Member,
+    intro: &Intro,
+) -> CreateMessage {
     CreateMessage::new()
-        .content(format!("Introduction: {user}"))
-        .embed(create_embed(user, intro))
+        .content(tr!(
+            ctx,
+            config.guild_locales(),
+            "intro-message",
+            user = member.user.to_string(),
+        ))
+        .embed(create_embed(
+            config,
+            &member.user,
+            member.display_name(),
+            intro,
+        ))
 }
 
-fn edit_message(user: &User, intro: &Intro) -> EditMessage {
-    EditMessage::new().embed(create_embed(user, intro))
+fn edit_message(config: &GuildConfig, member: &Member, intro: &Intro) -> EditMessage {
+    EditMessage::new().embed(create_embed(
+        config,
+        &member.user,
+        member.display_name(),
+        intro,
+    ))
 }
 
 #[tracing::instrument(skip_all)]
-async fn publish(ctx: &impl Context, member: &Member, intro: &Intro) -> Result<Message> {
+pub async fn publish(ctx: &impl Context, member: &Member, intro: &Intro) -> Result<Message> {
     let config = ctx.config().guild(member.guild_id)?;
 
     let mut tx = ctx.db().begin().await?;
 
+    persist::intro::set(
+        &mut *tx,
+        member.guild_id,
+        member.user.id,
+        &intro.fields,
+        chrono::Utc::now(),
+    )
+    .await?;
+
     let message = if let Some((channel_id, message_id)) =
         persist::intro_message::get(&mut *tx, member.guild_id, member.user.id).await?
     {
@@ -202,13 +316,13 @@ async fn publish(ctx: &impl Context, member: &Member, intro: &Intro) -> Result<M
             .edit_message(
                 ctx.serenity(),
                 message_id,
-                edit_message(&member.user, intro),
+                edit_message(config, member, intro),
             )
             .await?
     } else {
         let message = config
             .intros_channel
-            .send_message(ctx.serenity(), create_message(&member.user, intro))
+            .send_message(ctx.serenity(), create_message(ctx, config, member, intro))
             .await?;
 
         persist::intro_message::set(
@@ -228,6 +342,73 @@ async fn publish(ctx: &impl Context, member: &Member, intro: &Intro) -> Result<M
     Ok(message)
 }
 
+fn create_submitted_response(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    user_locale: &str,
+    in_review: bool,
+) -> CreateInteractionResponse {
+    let content = if in_review {
+        tr!(ctx, config.user_locales(user_locale), "review-submitted")
+    } else {
+        tr!(ctx, config.user_locales(user_locale), "intro-submitted")
+    };
+
+    CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content(content)
+            .ephemeral(true),
+    )
+}
+
+/// The channel where the quarantined member's intro must be approved, if it needs to be.
+async fn get_review_channel(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    member: &Member,
+) -> Result<Option<ChannelId>> {
+    if config.review_channel.is_some() {
+        return Ok(config.review_channel);
+    }
+
+    if new_accounts::was_new(ctx, config, member.guild_id, &member.user).await?
+        || lockdown::holds_for_review(ctx, config, member.guild_id).await?
+    {
+        // The config is rejected if there isn't one.
+        return Ok(config.mod_channel);
+    }
+
+    Ok(None)
+}
+
+/// Lets the quarantined member in with their intro, or sends it to the moderators for review first.
+async fn complete_onboarding(
+    ctx: &impl Context,
+    member: &mut Member,
+    intro: &Intro,
+    review_channel: Option<ChannelId>,
+) -> Result<()> {
+    state::transition(ctx, member.guild_id, member.user.id, State::IntroSubmitted).await?;
+
+    let result = async {
+        if let Some(review_channel) = review_channel {
+            review::submit(ctx, member, intro, review_channel).await?;
+            state::transition(ctx, member.guild_id, member.user.id, State::UnderReview).await
+        } else {
+            publish(ctx, member, intro).await?;
+            unquarantine(ctx, member).await
+        }
+    }
+    .await;
+
+    if result.is_err() {
+        // They're still quarantined, and should be reminded and kicked as such.
+        state::transition(ctx, member.guild_id, member.user.id, State::Welcomed).await?;
+    }
+
+    result
+}
+
 #[tracing::instrument(skip_all)]
 pub async fn submit(ctx: &impl Context, interaction: &ModalInteraction) -> Result<()> {
     let mut member = interaction
@@ -235,62 +416,242 @@ pub async fn submit(ctx: &impl Context, interaction: &ModalInteraction) -> Resul
         .clone()
         .context("Interaction has no member")?;
     let config = ctx.config().guild(member.guild_id)?;
-    let intro = Intro::from_modal_interaction(interaction)?;
+    let intro = Intro::from_modal_interaction(&config.intro_questions, interaction)?;
+
+    let welcome_thread =
+        persist::welcome_message::get_thread(ctx.db(), member.guild_id, member.user.id).await?;
+    let is_from_quarantine = interaction.message.as_ref().is_some_and(|message| {
+        message.channel_id == config.quarantine_channel
+            || Some(message.channel_id) == welcome_thread
+    });
+
+    let is_quarantined = member.roles.contains(&config.quarantine_role);
+
+    // The modal may have been opened before the member completed the steps that come first.
+    if is_from_quarantine || is_quarantined {
+        let prompt = create_onboarding_prompt(
+            ctx,
+            config,
+            member.guild_id,
+            member.user.id,
+            &interaction.locale,
+        )
+        .await?;
 
-    // TODO: When Shuttle has updated to Rust 1.70, switch this to is_some_and
-    let is_from_quarantine = match &interaction.message {
-        Some(message) => message.channel_id == config.quarantine_channel,
-        None => false,
-    };
+        if let Some(prompt) = prompt {
+            interaction
+                .create_response(ctx.serenity(), CreateInteractionResponse::Message(prompt))
+                .await?;
+
+            return Ok(());
+        }
+    }
 
-    if is_from_quarantine {
-        let ack_content = "Thanks for submitting your introduction. In the next few seconds, you'll get access to the rest of the server.";
+    let findings = intro_check::check(ctx, config, &member, &intro).await?;
+    if !findings.is_empty() && intro_check::rejects(config) {
         interaction
             .create_response(
                 ctx.serenity(),
-                CreateInteractionResponse::Message(
-                    CreateInteractionResponseMessage::new()
-                        .content(ack_content)
-                        .ephemeral(true),
+                intro_check::create_rejected_response(ctx, config, &interaction.locale, &findings)?,
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let review_channel = get_review_channel(ctx, config, &member).await?;
+
+    if is_from_quarantine || (is_quarantined && review_channel.is_some()) {
+        interaction
+            .create_response(
+                ctx.serenity(),
+                create_submitted_response(
+                    ctx,
+                    config,
+                    &interaction.locale,
+                    review_channel.is_some(),
                 ),
             )
             .await?;
 
-        publish(ctx, &member, &intro).await?;
-        unquarantine(ctx, &mut member).await?;
+        complete_onboarding(ctx, &mut member, &intro, review_channel).await?;
     } else {
         let message = publish(ctx, &member, &intro).await?;
         let message_url = message.id.link(message.channel_id, Some(member.guild_id));
 
+        interaction
+            .create_response(
+                ctx.serenity(),
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new().content(tr!(
+                        ctx,
+                        config.user_locales(&interaction.locale),
+                        "intro-updated",
+                        url = message_url.to_string(),
+                    )),
+                ),
+            )
+            .await?;
+    }
+
+    if !findings.is_empty() {
+        intro_check::report_flagged(ctx, config, &member, &findings).await?;
+    }
+
+    Ok(())
+}
+
+/// Publishes the intro that was archived when the member left.
+#[tracing::instrument(skip_all)]
+pub async fn restore(ctx: &impl Context, interaction: &ComponentInteraction) -> Result<()> {
+    let mut member = interaction
+        .member
+        .clone()
+        .context("Interaction has no member")?;
+    let config = ctx.config().guild(member.guild_id)?;
+
+    let prompt = create_onboarding_prompt(
+        ctx,
+        config,
+        member.guild_id,
+        member.user.id,
+        &interaction.locale,
+    )
+    .await?;
+
+    if let Some(prompt) = prompt {
+        interaction
+            .create_response(ctx.serenity(), CreateInteractionResponse::Message(prompt))
+            .await?;
+
+        return Ok(());
+    }
+
+    let Some(intro) = get_archived(ctx, member.guild_id, member.user.id).await? else {
         interaction
             .create_response(
                 ctx.serenity(),
                 CreateInteractionResponse::Message(
                     CreateInteractionResponseMessage::new()
-                        .content(format!("Introduction updated {message_url}")),
+                        .content(tr!(
+                            ctx,
+                            config.user_locales(&interaction.locale),
+                            "intro-restore-none",
+                        ))
+                        .ephemeral(true),
                 ),
             )
             .await?;
+
+        return Ok(());
+    };
+
+    let review_channel = get_review_channel(ctx, config, &member).await?;
+
+    interaction
+        .create_response(
+            ctx.serenity(),
+            create_submitted_response(ctx, config, &interaction.locale, review_channel.is_some()),
+        )
+        .await?;
+
+    complete_onboarding(ctx, &mut member, &intro, review_channel).await?;
+
+    info!(
+        %member.guild_id,
+        %member.user.id,
+        member.user.tag = member.user.tag(),
+        "Restored intro"
+    );
+
+    Ok(())
+}
+
+/// Removes the intro message from the intros channel, if it's published.
+/// Call in a transaction, so that the deletion isn't mistaken for a moderator's.
+pub async fn unpublish(
+    ctx: &impl Context,
+    conn: &mut PgConnection,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<()> {
+    if let Some((channel_id, message_id)) =
+        persist::intro_message::get(&mut *conn, guild_id, user_id).await?
+    {
+        // The row stays locked until the transaction ends, so the delete event finds nothing.
+        persist::intro_message::delete(&mut *conn, guild_id, user_id).await?;
+
+        channel_id
+            .delete_message(ctx.serenity(), message_id)
+            .await
+            .or_else(|err| {
+                if is_http_not_found(&err) {
+                    Ok(())
+                } else {
+                    Err(err)
+                }
+            })?;
     }
 
     Ok(())
 }
 
-pub async fn update_avatar(ctx: &impl Context, member: &Member) -> Result<()> {
+/// Removes the intro message of a member who left, keeping the intro so that it can be restored.
+#[tracing::instrument(skip(ctx))]
+pub async fn archive(ctx: &impl Context, guild_id: GuildId, user_id: UserId) -> Result<()> {
+    let mut tx = ctx.db().begin().await?;
+
+    unpublish(ctx, &mut tx, guild_id, user_id).await?;
+    persist::intro::archive(&mut *tx, guild_id, user_id, chrono::Utc::now()).await?;
+
+    tx.commit().await?;
+
+    info!(%guild_id, %user_id, "Archived intro");
+
+    Ok(())
+}
+
+/// Removes the intro message and deletes the intro, so that the member has to introduce themselves
+/// again. The revisions are kept.
+#[tracing::instrument(skip(ctx))]
+pub async fn reset(ctx: &impl Context, guild_id: GuildId, user_id: UserId) -> Result<()> {
+    let mut tx = ctx.db().begin().await?;
+
+    unpublish(ctx, &mut tx, guild_id, user_id).await?;
+    persist::intro::delete(&mut *tx, guild_id, user_id).await?;
+
+    tx.commit().await?;
+
+    info!(%guild_id, %user_id, "Reset intro");
+
+    Ok(())
+}
+
+/// Whether the intro embed looks different after the member update.
+pub fn profile_changed(old: &Member, new: &Member) -> bool {
+    old.user.avatar != new.user.avatar || old.display_name() != new.display_name()
+}
+
+/// Re-renders the published intro with the member's current avatar and display name.
+#[tracing::instrument(skip_all, fields(%member.guild_id, %member.user.id))]
+pub async fn update_profile(ctx: &impl Context, member: &Member) -> Result<()> {
+    let config = ctx.config().guild(member.guild_id)?;
+
     let Some((channel_id, message_id)) =
         persist::intro_message::get(ctx.db(), member.guild_id, member.user.id).await?
     else {
         return Ok(());
     };
 
-    let message = channel_id.message(ctx.serenity(), message_id).await?;
-    let intro = Intro::from_message_embeds(message)?;
+    let Some(intro) = get(ctx, member.guild_id, member.user.id).await? else {
+        return Ok(());
+    };
 
     channel_id
         .edit_message(
             ctx.serenity(),
             message_id,
-            edit_message(&member.user, &intro),
+            edit_message(config, member, &intro),
         )
         .await?;
 
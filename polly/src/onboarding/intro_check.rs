@@ -0,0 +1,338 @@
+use std::{collections::BTreeMap, fmt::Write, sync::LazyLock};
+
+use regex::Regex;
+use serenity::all::{
+    CreateInteractionResponse,
+    CreateInteractionResponseMessage,
+    Member,
+    Mentionable,
+    UserId,
+};
+
+use super::{intro::Intro, persist, report};
+use crate::{
+    config::{GuildConfig, IntroCheckAction, IntroCheckConfig},
+    context::Context,
+    error::Result,
+    l10n::tr,
+};
+
+static INVITE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(discord\.gg|discord(app)?\.com/invite)/").unwrap());
+static LINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(https?://|www\.)\S").unwrap());
+
+/// Longest pattern that counts as repeated, as in "asdf".
+const MAX_PATTERN_LEN: usize = 4;
+
+/// Why an intro was caught.
+#[derive(Debug)]
+pub enum Finding {
+    RepeatedCharacters,
+    Invite,
+    Link,
+    Duplicate(UserId),
+    BlockedWord(String),
+}
+
+/// A check that only looks at the intro itself.
+type Check = fn(&IntroCheckConfig, &str) -> Option<Finding>;
+
+const CHECKS: &[Check] = &[
+    check_repeated_characters,
+    check_invites,
+    check_links,
+    check_blocked_words,
+];
+
+/// Joins the answers like `onboarding_intro_text` does in the database.
+fn join_fields(fields: &BTreeMap<String, String>) -> String {
+    fields
+        .values()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Share of characters that are part of a pattern repeated at least three times in a row,
+/// ignoring whitespace.
+#[allow(clippy::cast_precision_loss)]
+fn repeated_ratio(text: &str) -> f64 {
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if chars.is_empty() {
+        return 0.0;
+    }
+
+    let mut repeated = vec![false; chars.len()];
+    for len in 1..=MAX_PATTERN_LEN {
+        let mut run = 0;
+        for i in len..chars.len() {
+            if chars[i] != chars[i - len] {
+                run = 0;
+                continue;
+            }
+
+            run += 1;
+            if run == 2 * len {
+                repeated[i + 1 - run - len..=i].fill(true);
+            } else if run > 2 * len {
+                repeated[i] = true;
+            }
+        }
+    }
+
+    repeated.iter().filter(|&&r| r).count() as f64 / chars.len() as f64
+}
+
+fn check_repeated_characters(config: &IntroCheckConfig, text: &str) -> Option<Finding> {
+    (repeated_ratio(text) > config.max_repeated_ratio).then_some(Finding::RepeatedCharacters)
+}
+
+fn check_invites(_config: &IntroCheckConfig, text: &str) -> Option<Finding> {
+    INVITE_RE.is_match(text).then_some(Finding::Invite)
+}
+
+fn check_links(config: &IntroCheckConfig, text: &str) -> Option<Finding> {
+    (!config.allow_links && !INVITE_RE.is_match(text) && LINK_RE.is_match(text))
+        .then_some(Finding::Link)
+}
+
+fn words(text: &str) -> impl Iterator<Item = String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn check_blocked_words(config: &IntroCheckConfig, text: &str) -> Option<Finding> {
+    words(text)
+        .find(|word| config.blocked_words.contains(word))
+        .map(Finding::BlockedWord)
+}
+
+async fn check_duplicate(
+    ctx: &impl Context,
+    config: &IntroCheckConfig,
+    member: &Member,
+    text: &str,
+) -> Result<Option<Finding>> {
+    // Greetings alone are too short to tell duplicates apart.
+    if words(text).nth(1).is_none() {
+        return Ok(None);
+    }
+
+    let mut conn = ctx.db().acquire().await?;
+    let duplicate = persist::intro::find_similar(
+        &mut conn,
+        member.guild_id,
+        member.user.id,
+        text,
+        config.max_similarity,
+    )
+    .await?
+    .map(Finding::Duplicate);
+
+    Ok(duplicate)
+}
+
+/// Runs the configured checks on the intro. Returns nothing if checks are disabled.
+#[tracing::instrument(skip_all, fields(%member.guild_id, %member.user.id))]
+pub async fn check(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    member: &Member,
+    intro: &Intro,
+) -> Result<Vec<Finding>> {
+    let Some(check_config) = &config.intro_check else {
+        return Ok(Vec::new());
+    };
+
+    let text = join_fields(&intro.fields);
+
+    let mut findings: Vec<Finding> = CHECKS
+        .iter()
+        .filter_map(|check| check(check_config, &text))
+        .collect();
+    if let Some(finding) = check_duplicate(ctx, check_config, member, &text).await? {
+        findings.push(finding);
+    }
+
+    if !findings.is_empty() {
+        tracing::info!(?findings, "Intro was caught by checks");
+    }
+
+    Ok(findings)
+}
+
+/// Whether caught intros are rejected, instead of flagged to the moderators.
+pub fn rejects(config: &GuildConfig) -> bool {
+    config
+        .intro_check
+        .as_ref()
+        .is_some_and(|check_config| check_config.action == IntroCheckAction::Reject)
+}
+
+fn write_findings(
+    ctx: &impl Context,
+    locales: &[&str],
+    w: &mut impl Write,
+    findings: &[Finding],
+    show_duplicate: bool,
+) -> Result<()> {
+    for finding in findings {
+        let reason = match finding {
+            Finding::RepeatedCharacters => tr!(ctx, locales, "intro-check-repeated"),
+            Finding::Invite => tr!(ctx, locales, "intro-check-invite"),
+            Finding::Link => tr!(ctx, locales, "intro-check-link"),
+            Finding::Duplicate(user_id) if show_duplicate => tr!(
+                ctx,
+                locales,
+                "intro-check-duplicate-of",
+                member = user_id.mention().to_string(),
+            ),
+            Finding::Duplicate(_) => tr!(ctx, locales, "intro-check-duplicate"),
+            Finding::BlockedWord(word) => {
+                tr!(
+                    ctx,
+                    locales,
+                    "intro-check-blocked-word",
+                    word = word.as_str(),
+                )
+            }
+        };
+        writeln!(w, "- {reason}")?;
+    }
+
+    Ok(())
+}
+
+/// Explains to the member why their intro wasn't accepted.
+pub fn create_rejected_response(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    user_locale: &str,
+    findings: &[Finding],
+) -> Result<CreateInteractionResponse> {
+    let locales = config.user_locales(user_locale);
+
+    let mut content = String::new();
+    writeln!(content, "{}", tr!(ctx, locales, "intro-check-rejected"))?;
+    write_findings(ctx, &locales, &mut content, findings, false)?;
+
+    Ok(CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content(content)
+            .ephemeral(true),
+    ))
+}
+
+/// Tells the moderators about an intro that was let through.
+pub async fn report_flagged(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    member: &Member,
+    findings: &[Finding],
+) -> Result<()> {
+    let locales = config.guild_locales();
+
+    let location = persist::intro_message::get(ctx.db(), member.guild_id, member.user.id)
+        .await?
+        .map_or_else(
+            || tr!(ctx, locales, "intro-check-unpublished"),
+            |(channel_id, message_id)| {
+                message_id
+                    .link(channel_id, Some(member.guild_id))
+                    .to_string()
+            },
+        );
+
+    let mut text = String::new();
+    writeln!(
+        text,
+        "{}",
+        tr!(
+            ctx,
+            locales,
+            "intro-check-report",
+            member = member.mention().to_string(),
+            tag = member.user.tag(),
+            location = location,
+        ),
+    )?;
+    write_findings(ctx, &locales, &mut text, findings, true)?;
+
+    report(ctx, config, text, "flagged_intro.md").await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(allow_links: bool) -> IntroCheckConfig {
+        IntroCheckConfig {
+            action: IntroCheckAction::Reject,
+            max_repeated_ratio: 0.3,
+            allow_links,
+            max_similarity: 0.8,
+            blocked_words: vec!["spam".to_owned()],
+        }
+    }
+
+    #[test]
+    fn repeated_ratio_of_ordinary_text_is_low() {
+        assert!(repeated_ratio("Hi, I'm Sam and I love hiking in the mountains.") < 0.1);
+        assert!(repeated_ratio("") < f64::EPSILON);
+    }
+
+    #[test]
+    fn repeated_ratio_counts_repeated_patterns() {
+        assert!((repeated_ratio("aaaaaaaa") - 1.0).abs() < f64::EPSILON);
+        assert!((repeated_ratio("asdf asdf asdf") - 1.0).abs() < f64::EPSILON);
+        // Patterns repeated only twice, like "ll" or "haha", aren't counted.
+        assert!(repeated_ratio("hello haha") < f64::EPSILON);
+        assert!((repeated_ratio("hi xxxxxx") - 0.75).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn invites_are_caught_even_if_links_are_allowed() {
+        let config = config(true);
+
+        for text in [
+            "join discord.gg/abc",
+            "https://discord.com/invite/abc",
+            "DISCORDAPP.COM/invite/abc",
+        ] {
+            assert!(
+                matches!(check_invites(&config, text), Some(Finding::Invite)),
+                "{text}"
+            );
+            assert!(check_links(&config, text).is_none(), "{text}");
+        }
+    }
+
+    #[test]
+    fn links_are_caught_unless_allowed() {
+        for text in [
+            "see https://example.com",
+            "www.example.com",
+            "HTTP://example.com",
+        ] {
+            assert!(
+                matches!(check_links(&config(false), text), Some(Finding::Link)),
+                "{text}"
+            );
+            assert!(check_links(&config(true), text).is_none(), "{text}");
+            assert!(check_invites(&config(false), text).is_none(), "{text}");
+        }
+
+        assert!(check_links(&config(false), "I like example.com and https://").is_none());
+    }
+
+    #[test]
+    fn blocked_words_are_caught_regardless_of_case() {
+        assert!(matches!(
+            check_blocked_words(&config(false), "No SPAM here"),
+            Some(Finding::BlockedWord(word)) if word == "spam",
+        ));
+        assert!(check_blocked_words(&config(false), "spammers are words too").is_none());
+    }
+}
@@ -0,0 +1,214 @@
+use anyhow::Context as _;
+use chrono::{DateTime, Utc};
+use serenity::all::{CreateInteractionResponseMessage, GuildId, Member};
+use tracing::{info, warn};
+
+use super::{format_duration, persist};
+use crate::{
+    PoiseApplicationContext,
+    config::{GuildConfig, LockdownAction},
+    context::Context,
+    error::Result,
+    l10n::tr,
+};
+
+/// The action of the active lockdown, if any.
+async fn get_active_action(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_id: GuildId,
+) -> Result<Option<LockdownAction>> {
+    let Some(lockdown_config) = &config.lockdown else {
+        return Ok(None);
+    };
+
+    let is_active = persist::lockdown::get_active(ctx.db(), guild_id)
+        .await?
+        .is_some();
+
+    Ok(is_active.then_some(lockdown_config.action))
+}
+
+/// Whether quarantined members currently can't introduce themselves.
+pub async fn pauses_intros(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_id: GuildId,
+) -> Result<bool> {
+    let action = get_active_action(ctx, config, guild_id).await?;
+
+    Ok(action == Some(LockdownAction::PauseIntros))
+}
+
+/// When intros were last paused by a lockdown that has ended, if ever.
+/// Members get the full deadline to introduce themselves from then.
+pub async fn intros_resumed_at(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_id: GuildId,
+) -> Result<Option<DateTime<Utc>>> {
+    let pauses_intros = config
+        .lockdown
+        .as_ref()
+        .is_some_and(|lockdown_config| lockdown_config.action == LockdownAction::PauseIntros);
+    if !pauses_intros {
+        return Ok(None);
+    }
+
+    persist::lockdown::get_last_ended(ctx.db(), guild_id).await
+}
+
+/// Whether intros of quarantined members currently must be approved by a moderator.
+pub async fn holds_for_review(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_id: GuildId,
+) -> Result<bool> {
+    let action = get_active_action(ctx, config, guild_id).await?;
+
+    Ok(action == Some(LockdownAction::HoldForReview))
+}
+
+pub fn create_paused_prompt(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    user_locale: &str,
+) -> CreateInteractionResponseMessage {
+    CreateInteractionResponseMessage::new()
+        .content(tr!(
+            ctx,
+            config.user_locales(user_locale),
+            "lockdown-paused",
+        ))
+        .ephemeral(true)
+}
+
+/// Sends the alert to the mod channel, or to the errors channel without one.
+async fn alert(ctx: &impl Context, config: &GuildConfig, text: String) -> Result<()> {
+    let channel_id = config.mod_channel.unwrap_or(ctx.config().errors_channel);
+    channel_id.say(ctx.serenity(), text).await?;
+
+    Ok(())
+}
+
+/// Starts a lockdown if too many members joined recently. Call after recording the join.
+#[tracing::instrument(skip_all, fields(%member.guild_id))]
+pub async fn check_join_rate(ctx: &impl Context, member: &Member) -> Result<()> {
+    let config = ctx.config().guild(member.guild_id)?;
+    let Some(lockdown_config) = &config.lockdown else {
+        return Ok(());
+    };
+
+    let now = chrono::Utc::now();
+    let since = now - lockdown_config.window;
+    let n_joined = persist::lifecycle::count_joined_since(ctx.db(), member.guild_id, since).await?;
+
+    if n_joined < i64::from(lockdown_config.max_joins) {
+        return Ok(());
+    }
+
+    // Only the join that started the lockdown alerts.
+    if !persist::lockdown::start(ctx.db(), member.guild_id, now).await? {
+        return Ok(());
+    }
+
+    warn!(n_joined, "Join raid detected, started lockdown");
+
+    let locales = config.guild_locales();
+    alert(
+        ctx,
+        config,
+        tr!(
+            ctx,
+            locales,
+            "lockdown-alert",
+            count = n_joined,
+            window = format_duration(ctx, &locales, lockdown_config.window),
+            action = action_description(ctx, &locales, lockdown_config.action),
+        ),
+    )
+    .await
+}
+
+fn action_description(ctx: &impl Context, locales: &[&str], action: LockdownAction) -> String {
+    match action {
+        LockdownAction::Alert => tr!(ctx, locales, "lockdown-action-alert"),
+        LockdownAction::PauseIntros => tr!(ctx, locales, "lockdown-action-pause-intros"),
+        LockdownAction::HoldForReview => tr!(ctx, locales, "lockdown-action-hold-for-review"),
+    }
+}
+
+/// Control the lockdown that protects onboarding from join raids
+#[poise::command(
+    guild_only,
+    slash_command,
+    subcommand_required,
+    subcommands("start", "end")
+)]
+#[allow(clippy::unused_async)] // Never called, because a subcommand is required.
+pub async fn lockdown(_ctx: PoiseApplicationContext<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Lock onboarding down, as if a join raid was detected
+#[poise::command(guild_only, slash_command)]
+#[tracing::instrument(
+    fields(
+        ctx.id = ctx.id(),
+        ctx.guild_id = %ctx.guild_id().unwrap_or_default(),
+        ctx.author.id = %ctx.author().id,
+    ),
+    skip(ctx),
+)]
+pub async fn start(ctx: PoiseApplicationContext<'_>) -> Result<()> {
+    let guild_id = ctx.guild_id().context("Context has no guild_id")?;
+    let config = ctx.config().guild(guild_id)?;
+    let locales = config.user_locales(&ctx.interaction.locale);
+
+    let content = if let Some(lockdown_config) = &config.lockdown {
+        if persist::lockdown::start(ctx.db(), guild_id, chrono::Utc::now()).await? {
+            info!("Moderator started lockdown");
+            tr!(
+                &ctx,
+                locales,
+                "lockdown-started",
+                action = action_description(&ctx, &locales, lockdown_config.action),
+            )
+        } else {
+            tr!(&ctx, locales, "lockdown-already-active")
+        }
+    } else {
+        tr!(&ctx, locales, "lockdown-not-configured")
+    };
+
+    ctx.say(content).await?;
+
+    Ok(())
+}
+
+/// End the lockdown
+#[poise::command(guild_only, slash_command)]
+#[tracing::instrument(
+    fields(
+        ctx.id = ctx.id(),
+        ctx.guild_id = %ctx.guild_id().unwrap_or_default(),
+        ctx.author.id = %ctx.author().id,
+    ),
+    skip(ctx),
+)]
+pub async fn end(ctx: PoiseApplicationContext<'_>) -> Result<()> {
+    let guild_id = ctx.guild_id().context("Context has no guild_id")?;
+    let config = ctx.config().guild(guild_id)?;
+    let locales = config.user_locales(&ctx.interaction.locale);
+
+    let content = if persist::lockdown::end(ctx.db(), guild_id, chrono::Utc::now()).await? {
+        info!("Moderator ended lockdown");
+        tr!(&ctx, locales, "lockdown-ended")
+    } else {
+        tr!(&ctx, locales, "lockdown-not-active")
+    };
+
+    ctx.say(content).await?;
+
+    Ok(())
+}
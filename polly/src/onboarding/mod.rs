@@ -1,33 +1,63 @@
+mod age;
+mod deleted_messages;
+mod history;
 mod intro;
+mod intro_check;
+mod lockdown;
+mod moderation;
+mod mydata;
+mod new_accounts;
 mod persist;
+mod preview;
 mod quarantine;
+mod reminders;
+mod review;
+mod rules;
+mod search;
+mod state;
+mod stats;
+mod view;
 
 use std::{
     collections::{HashMap, HashSet},
     future,
+    time::{Duration, Instant},
 };
 
 use anyhow::Context as _;
+use chrono::TimeDelta;
 use futures::TryStreamExt;
-use poise::CommandInteractionType;
+use poise::{CommandInteractionType, CreateReply};
 use serenity::all::{
     ComponentInteraction,
+    CreateAttachment,
     CreateInteractionResponse,
+    CreateInteractionResponseMessage,
+    CreateMessage,
     FullEvent,
     GuildId,
     Interaction,
+    MESSAGE_CODE_LIMIT,
     Member,
     Message,
     ModalInteraction,
     User,
+    UserId,
 };
+use sqlx::PgConnection;
 
-use self::quarantine::{delete_welcome_message, quarantine};
+use self::{
+    intro::Intro,
+    persist::state::State,
+    quarantine::{deadline_start, delete_welcome_message, quarantine},
+};
+pub use self::{mydata::mydata, reminders::send_reminders, rules::check_rules, view::view_intro};
 use crate::{
     PoiseApplicationContext,
     config::GuildConfig,
     context::Context,
     error::{Error, Result},
+    l10n::tr,
 };
 
 #[tracing::instrument(
@@ -43,10 +73,20 @@ async fn guild_member_addition(ctx: &impl Context, member: &Member) -> Result<()
         return Ok(());
     }
 
+    state::transition(ctx, member.guild_id, member.user.id, State::Joined).await?;
+    lockdown::check_join_rate(ctx, member).await?;
+
+    let config = ctx.config().guild(member.guild_id)?;
+    if let Some(account_age) = new_accounts::get_age(config, &member.user) {
+        new_accounts::report_joined(ctx, config, member, account_age).await?;
+    }
+
     if intro::get(ctx, member.guild_id, member.user.id)
         .await?
-        .is_none()
+        .is_some()
     {
+        state::transition(ctx, member.guild_id, member.user.id, State::Member).await?;
+    } else {
         quarantine(ctx, member).await?;
     }
 
@@ -66,19 +106,39 @@ async fn guild_member_removal(ctx: &impl Context, guild_id: &GuildId, user: &Use
         return Ok(());
     }
 
+    reminders::delete_reminders(ctx, *guild_id, user.id).await?;
     delete_welcome_message(ctx, *guild_id, user.id).await?;
 
+    let config = ctx.config().guild(*guild_id)?;
+    if config.archive_intros {
+        intro::archive(ctx, *guild_id, user.id).await?;
+    }
+    review::cancel(ctx, *guild_id, user.id).await?;
+
+    state::transition(ctx, *guild_id, user.id, State::Left).await?;
+
     Ok(())
 }
 
-async fn guild_member_update(ctx: &impl Context, new: Option<&Member>) -> Result<()> {
+async fn guild_member_update(
+    ctx: &impl Context,
+    old: Option<&Member>,
+    new: Option<&Member>,
+) -> Result<()> {
     let member = new.context("Member update has no new member")?;
 
     if member.user.bot {
         return Ok(());
     }
 
-    intro::update_avatar(ctx, member).await?;
+    state::sync_quarantine_role(ctx, old, member).await?;
+
+    // Without the old member in the cache, there's no telling what changed.
+    if old.is_some_and(|old| !intro::profile_changed(old, member)) {
+        return Ok(());
+    }
+
+    intro::update_profile(ctx, member).await?;
 
     Ok(())
 }
@@ -103,16 +163,49 @@ pub async fn component_interaction(
                 .member
                 .as_ref()
                 .context("Interaction has no member")?;
-            let modal = intro::create_modal_for_member(ctx, member).await?;
+            let config = ctx.config().guild(member.guild_id)?;
+
+            let prompt = if member.roles.contains(&config.quarantine_role) {
+                create_onboarding_prompt(
+                    ctx,
+                    config,
+                    member.guild_id,
+                    member.user.id,
+                    &interaction.locale,
+                )
+                .await?
+            } else {
+                None
+            };
+
+            let response = if let Some(prompt) = prompt {
+                CreateInteractionResponse::Message(prompt)
+            } else {
+                let modal =
+                    intro::create_modal_for_member(ctx, member, &interaction.locale).await?;
+                CreateInteractionResponse::Modal(modal)
+            };
 
             interaction
-                .create_response(ctx.serenity(), CreateInteractionResponse::Modal(modal))
+                .create_response(ctx.serenity(), response)
                 .await?;
 
             Ok(())
         }
 
-        _ => Ok(()),
+        intro::RESTORE_ID => intro::restore(ctx, interaction).await,
+
+        age::CONFIRM_ID => age::component_interaction(ctx, interaction).await,
+
+        custom_id => {
+            if let Some((action, user_id)) = review::Action::parse_custom_id(custom_id) {
+                review::component_interaction(ctx, interaction, action, user_id).await
+            } else if let Some((guild_id, version)) = rules::parse_custom_id(custom_id) {
+                rules::component_interaction(ctx, interaction, guild_id, version).await
+            } else {
+                Ok(())
+            }
+        }
     }
 }
 
@@ -130,7 +223,12 @@ pub async fn modal_interaction(ctx: &impl Context, interaction: &ModalInteractio
     match interaction.data.custom_id.as_str() {
         intro::MODAL_ID => intro::submit(ctx, interaction).await,
 
-        _ => Ok(()),
+        custom_id => match review::Decline::parse_custom_id(custom_id) {
+            Some((decline, user_id)) => {
+                review::modal_interaction(ctx, interaction, decline, user_id).await
+            }
+            None => Ok(()),
+        },
     }
 }
 
@@ -148,10 +246,33 @@ pub async fn handle_event(ctx: &impl Context, event: &FullEvent) -> Result<()> {
         } => guild_member_removal(ctx, guild_id, user).await,
 
         FullEvent::GuildMemberUpdate {
-            old_if_available: _,
+            old_if_available,
             new,
             event: _,
-        } => guild_member_update(ctx, new.as_ref()).await,
+        } => guild_member_update(ctx, old_if_available.as_ref(), new.as_ref()).await,
+
+        FullEvent::MessageDelete {
+            channel_id,
+            deleted_message_id,
+            guild_id: Some(guild_id),
+        } => {
+            deleted_messages::messages_deleted(ctx, *guild_id, *channel_id, &[*deleted_message_id])
+                .await
+        }
+
+        FullEvent::MessageDeleteBulk {
+            channel_id,
+            multiple_deleted_messages_ids,
+            guild_id: Some(guild_id),
+        } => {
+            deleted_messages::messages_deleted(
+                ctx,
+                *guild_id,
+                *channel_id,
+                multiple_deleted_messages_ids,
+            )
+            .await
+        }
 
         FullEvent::InteractionCreate {
             interaction: Interaction::Component(interaction),
@@ -185,6 +306,7 @@ pub async fn onboarding_sync_db(ctx: PoiseApplicationContext<'_>) -> Result<()>
     let bot_id = ctx.framework().bot_id;
     let guild_id = ctx.guild_id().context("Context has no guild_id")?;
     let config = ctx.config().guild(guild_id)?;
+    let locales = config.user_locales(&ctx.interaction.locale);
 
     let found_intros: Vec<Message> = config
         .intros_channel
@@ -203,6 +325,7 @@ pub async fn onboarding_sync_db(ctx: PoiseApplicationContext<'_>) -> Result<()>
 
     let mut n_added = 0;
     let mut n_deleted = 0;
+    let mut n_imported = 0;
 
     let mut tx = ctx.data().db.begin().await?;
 
@@ -220,10 +343,14 @@ pub async fn onboarding_sync_db(ctx: PoiseApplicationContext<'_>) -> Result<()>
     }
 
     for message in &found_intros {
+        let link = message.id.link(message.channel_id, Some(guild_id));
+
         let Some(user) = message.mentions.first() else {
-            ctx.say(format!(
-                "Intro message has no mentions: {}",
-                message.id.link(message.channel_id, Some(guild_id)),
+            ctx.say(tr!(
+                &ctx,
+                locales,
+                "sync-db-no-mentions",
+                link = link.to_string(),
             ))
             .await?;
 
@@ -233,27 +360,240 @@ pub async fn onboarding_sync_db(ctx: PoiseApplicationContext<'_>) -> Result<()>
         if let Some(&(persisted_channel_id, persisted_message_id)) = persisted_intros.get(&user.id)
         {
             if persisted_message_id != message.id {
-                ctx.say(format!(
-                    "Duplicate intro messages: {} {}",
-                    persisted_message_id.link(persisted_channel_id, Some(guild_id)),
-                    message.id.link(message.channel_id, Some(guild_id)),
+                ctx.say(tr!(
+                    &ctx,
+                    locales,
+                    "sync-db-duplicate",
+                    first = persisted_message_id
+                        .link(persisted_channel_id, Some(guild_id))
+                        .to_string(),
+                    second = link.to_string(),
                 ))
                 .await?;
+
+                continue;
             }
+        } else {
+            persist::intro_message::set(
+                &mut *tx,
+                guild_id,
+                user.id,
+                message.channel_id,
+                message.id,
+            )
+            .await?;
+            persisted_intros.insert(user.id, (message.channel_id, message.id));
+            n_added += 1;
+        }
 
-            continue;
+        if import_intro(ctx, &mut tx, config, message, user.id).await? {
+            n_imported += 1;
+        }
+    }
+
+    tx.commit().await?;
+
+    ctx.say(tr!(
+        &ctx,
+        locales,
+        "sync-db-summary",
+        added = n_added,
+        deleted = n_deleted,
+        imported = n_imported,
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Intros published before their contents were stored in the database only exist as embeds.
+/// Returns whether the contents were imported.
+async fn import_intro(
+    ctx: PoiseApplicationContext<'_>,
+    conn: &mut PgConnection,
+    config: &GuildConfig,
+    message: &Message,
+    user_id: UserId,
+) -> Result<bool> {
+    let guild_id = ctx.guild_id().context("Context has no guild_id")?;
+
+    if persist::intro::get(&mut *conn, guild_id, user_id)
+        .await?
+        .is_some()
+    {
+        return Ok(false);
+    }
+
+    match Intro::from_message_embeds(&config.intro_questions, message.clone()) {
+        Ok(intro) => {
+            persist::intro::set(
+                &mut *conn,
+                guild_id,
+                user_id,
+                &intro.fields,
+                *message.timestamp,
+            )
+            .await?;
+
+            Ok(true)
         }
 
-        persist::intro_message::set(&mut *tx, guild_id, user.id, message.channel_id, message.id)
+        Err(err) => {
+            ctx.say(tr!(
+                &ctx,
+                config.user_locales(&ctx.interaction.locale),
+                "sync-db-import-failed",
+                link = message
+                    .id
+                    .link(message.channel_id, Some(guild_id))
+                    .to_string(),
+                error = err.to_string(),
+            ))
             .await?;
-        persisted_intros.insert(user.id, (message.channel_id, message.id));
-        n_added += 1;
+
+            Ok(false)
+        }
     }
+}
 
-    tx.commit().await?;
+/// Replies ephemerally, attaching the text as a file if it doesn't fit in a message.
+async fn reply_long(ctx: PoiseApplicationContext<'_>, text: String, filename: &str) -> Result<()> {
+    let reply = if text.chars().count() > MESSAGE_CODE_LIMIT {
+        let guild_id = ctx.guild_id().context("Context has no guild_id")?;
+        let config = ctx.config().guild(guild_id)?;
+
+        CreateReply::default()
+            .content(tr!(
+                &ctx,
+                config.user_locales(&ctx.interaction.locale),
+                "reply-attached",
+            ))
+            .attachment(CreateAttachment::bytes(text, filename))
+    } else {
+        CreateReply::default().content(text)
+    };
 
-    ctx.say(format!("Intros: added {n_added}, deleted {n_deleted}"))
-        .await?;
+    ctx.send(reply.ephemeral(true)).await?;
+
+    Ok(())
+}
+
+/// Sends the report to the moderators, attaching the text as a file if it doesn't fit in a message.
+async fn report(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    text: String,
+    filename: &str,
+) -> Result<()> {
+    let Some(mod_channel) = config.mod_channel else {
+        tracing::warn!(report = text, "No mod channel to send report to");
+        return Ok(());
+    };
+
+    let message = if text.chars().count() > MESSAGE_CODE_LIMIT {
+        CreateMessage::new()
+            .content(tr!(ctx, config.guild_locales(), "reply-attached"))
+            .add_file(CreateAttachment::bytes(text, filename))
+    } else {
+        CreateMessage::new().content(text)
+    };
+
+    mod_channel.send_message(ctx.serenity(), message).await?;
+
+    Ok(())
+}
+
+/// Formats a duration roughly, like "2d 5h".
+fn format_duration(ctx: &impl Context, locales: &[&str], duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    let hours = minutes / 60;
+    let days = hours / 24;
+
+    if days > 0 {
+        tr!(
+            ctx,
+            locales,
+            "duration-days",
+            days = days,
+            hours = hours % 24
+        )
+    } else if hours > 0 {
+        tr!(
+            ctx,
+            locales,
+            "duration-hours",
+            hours = hours,
+            minutes = minutes % 60,
+        )
+    } else {
+        tr!(ctx, locales, "duration-minutes", minutes = minutes)
+    }
+}
+
+/// Asks the member to complete the next step that comes before introducing themselves, if any.
+async fn create_onboarding_prompt(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_id: GuildId,
+    user_id: UserId,
+    user_locale: &str,
+) -> Result<Option<CreateInteractionResponseMessage>> {
+    if lockdown::pauses_intros(ctx, config, guild_id).await? {
+        return Ok(Some(lockdown::create_paused_prompt(
+            ctx,
+            config,
+            user_locale,
+        )));
+    }
+
+    if !age::is_confirmed(ctx, guild_id, user_id).await? {
+        return Ok(Some(age::create_prompt(ctx, config, user_locale)));
+    }
+
+    if !rules::is_accepted(ctx, config, guild_id, user_id).await? {
+        return Ok(Some(rules::create_prompt(
+            ctx,
+            config,
+            guild_id,
+            user_locale,
+        )));
+    }
+
+    Ok(None)
+}
+
+/// Moderate onboarding
+#[poise::command(
+    default_member_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    required_permissions = "MODERATE_MEMBERS",
+    slash_command,
+    subcommand_required,
+    subcommands(
+        "age::age",
+        "history::history",
+        "lockdown::lockdown",
+        "moderation::quarantine_member",
+        "moderation::release",
+        "moderation::reset_intro",
+        "preview::preview",
+        "state::state",
+        "stats::stats"
+    )
+)]
+#[allow(clippy::unused_async)] // Never called, because a subcommand is required.
+pub async fn onboarding(_ctx: PoiseApplicationContext<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Introductions
+#[poise::command(
+    guild_only,
+    slash_command,
+    subcommand_required,
+    subcommands("edit", "search::search", "view::view")
+)]
+#[allow(clippy::unused_async)] // Never called, because a subcommand is required.
+pub async fn intro(_ctx: PoiseApplicationContext<'_>) -> Result<()> {
     Ok(())
 }
 
@@ -268,19 +608,36 @@ pub async fn onboarding_sync_db(ctx: PoiseApplicationContext<'_>) -> Result<()>
     ),
     skip(ctx),
 )]
-pub async fn intro(ctx: PoiseApplicationContext<'_>) -> Result<()> {
+pub async fn edit(ctx: PoiseApplicationContext<'_>) -> Result<()> {
     if ctx.interaction_type != CommandInteractionType::Command {
         return Ok(());
     }
 
     let member = ctx.author_member().await.context("Context has no member")?;
+    let config = ctx.config().guild(member.guild_id)?;
+
+    let prompt = if member.roles.contains(&config.quarantine_role) {
+        create_onboarding_prompt(
+            &ctx,
+            config,
+            member.guild_id,
+            member.user.id,
+            &ctx.interaction.locale,
+        )
+        .await?
+    } else {
+        None
+    };
+
+    let response = if let Some(prompt) = prompt {
+        CreateInteractionResponse::Message(prompt)
+    } else {
+        let modal = intro::create_modal_for_member(&ctx, &member, &ctx.interaction.locale).await?;
+        CreateInteractionResponse::Modal(modal)
+    };
 
-    let modal = intro::create_modal_for_member(&ctx, &member).await?;
     ctx.interaction
-        .create_response(
-            ctx.serenity_context,
-            CreateInteractionResponse::Modal(modal),
-        )
+        .create_response(ctx.serenity_context, response)
         .await?;
 
     Ok(())
@@ -301,77 +658,209 @@ fn connected_configured_guilds(
         })
 }
 
+/// How long after joining members should have been welcomed.
+const JOINED_GRACE: TimeDelta = TimeDelta::minutes(5);
+
+/// How long after submitting their intro members should have been let in or sent to review.
+const INTRO_SUBMITTED_GRACE: TimeDelta = TimeDelta::minutes(5);
+
+/// The guild's members, except bots. Read from the cache if it has all of them.
+async fn get_members(ctx: &impl Context, guild_id: GuildId) -> Result<Vec<Member>> {
+    let cached = ctx.serenity().cache.guild(guild_id).and_then(|guild| {
+        (guild.members.len() as u64 == guild.member_count).then(|| {
+            guild
+                .members
+                .values()
+                .filter(|member| !member.user.bot)
+                .cloned()
+                .collect()
+        })
+    });
+    if let Some(members) = cached {
+        return Ok(members);
+    }
+
+    let members = guild_id
+        .members_iter(ctx.serenity())
+        .err_into::<Error>()
+        .try_filter(|member| future::ready(!member.user.bot))
+        .try_collect()
+        .await?;
+
+    Ok(members)
+}
+
+/// The members of the guild who have a published intro.
+async fn get_introduced(ctx: &impl Context, guild_id: GuildId) -> Result<HashSet<UserId>> {
+    let introduced = persist::intro_message::get_all(ctx.db(), guild_id)
+        .await?
+        .into_iter()
+        .map(|(user_id, _, _)| user_id)
+        .collect();
+
+    Ok(introduced)
+}
+
+/// Whether `check_quarantine` should quarantine the member, because they were never welcomed.
+fn needs_quarantine(
+    config: &GuildConfig,
+    member: &Member,
+    state: Option<(State, chrono::DateTime<chrono::Utc>)>,
+    introduced: bool,
+    now: chrono::DateTime<chrono::Utc>,
+) -> bool {
+    if introduced {
+        return false;
+    }
+
+    match state {
+        // Members who joined before states were recorded.
+        None => !member.roles.contains(&config.quarantine_role),
+        // Leave time for welcoming members who just joined.
+        Some((State::Joined, since)) => since < now - JOINED_GRACE,
+        Some(_) => false,
+    }
+}
+
+/// The state of a member who was never welcomed but doesn't need it,
+/// or whose intro submission was interrupted.
+fn recovered_state(
+    config: &GuildConfig,
+    member: &Member,
+    state: Option<(State, chrono::DateTime<chrono::Utc>)>,
+    introduced: bool,
+    pending_review: bool,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<State> {
+    let recoverable = match state {
+        None | Some((State::Joined, _)) => true,
+        // Otherwise, they would be neither reminded nor kicked.
+        Some((State::IntroSubmitted, since)) => since < now - INTRO_SUBMITTED_GRACE,
+        Some(_) => false,
+    };
+    if !recoverable {
+        return None;
+    }
+
+    if member.roles.contains(&config.quarantine_role) {
+        // Members whose intro is under review are waiting on a moderator, and aren't reminded
+        // or kicked.
+        Some(if pending_review {
+            State::UnderReview
+        } else {
+            State::Welcomed
+        })
+    } else if introduced {
+        Some(State::Member)
+    } else {
+        None
+    }
+}
+
+/// Whether `kick_inactive` should kick the member.
+async fn needs_kick(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    member: &Member,
+    state: Option<State>,
+    intros_resumed_at: Option<chrono::DateTime<chrono::Utc>>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<bool> {
+    // Members whose intro is under review are waiting on a moderator, and aren't punished for it.
+    if state != Some(State::Welcomed) {
+        return Ok(false);
+    }
+
+    let cutoff = now - config.onboarding_deadline;
+    let old = deadline_start(ctx, member, intros_resumed_at)
+        .await?
+        .is_some_and(|start| start < cutoff);
+
+    Ok(old)
+}
+
 pub async fn check_quarantine(ctx: &impl Context) -> Result<()> {
     for (guild_id, config) in connected_configured_guilds(ctx) {
-        guild_id
-            .members_iter(ctx.serenity())
-            .err_into::<Error>()
-            .try_filter(|member| future::ready(!member.user.bot))
-            .try_for_each(|member| async move {
-                let introduced = persist::intro_message::get(ctx.db(), guild_id, member.user.id)
-                    .await?
-                    .is_some();
-                let quarantined = member.roles.contains(&config.quarantine_role);
-
-                if !introduced && !quarantined {
-                    tracing::warn!(
-                        %member.guild_id,
-                        %member.user.id,
-                        member.user.tag = member.user.tag(),
-                        "Member has no intro and is not quarantined",
-                    );
-                    quarantine(ctx, &member).await?;
-                }
+        let started = Instant::now();
+
+        let now = chrono::Utc::now();
+
+        let members = get_members(ctx, guild_id).await?;
+        let introduced = get_introduced(ctx, guild_id).await?;
+        let states = persist::state::get_all(ctx.db(), guild_id).await?;
+        let pending_reviews: HashSet<_> =
+            persist::intro_review::get_all_pending(ctx.db(), guild_id)
+                .await?
+                .into_iter()
+                .collect();
+
+        let mut n_quarantined = 0;
+        for member in &members {
+            let state = states.get(&member.user.id).copied();
+            let is_introduced = introduced.contains(&member.user.id);
+            let pending_review = pending_reviews.contains(&member.user.id);
+
+            if needs_quarantine(config, member, state, is_introduced, now) {
+                tracing::warn!(
+                    %member.guild_id,
+                    %member.user.id,
+                    member.user.tag = member.user.tag(),
+                    ?state,
+                    "Member was never welcomed",
+                );
+                quarantine(ctx, member).await?;
+                n_quarantined += 1;
+            } else if let Some(recovered) =
+                recovered_state(config, member, state, is_introduced, pending_review, now)
+            {
+                state::transition(ctx, guild_id, member.user.id, recovered).await?;
+            }
+        }
 
-                Ok(())
-            })
-            .await?;
+        tracing::info!(
+            %guild_id,
+            n_members = members.len(),
+            n_quarantined,
+            elapsed = ?started.elapsed(),
+            "Checked quarantine",
+        );
     }
 
     Ok(())
 }
 
 pub async fn kick_inactive(ctx: &impl Context) -> Result<()> {
-    const INACTIVE_DAYS: i64 = 7;
-    const REASON: &str = "Onboarding not completed"; // Shows in the audit log.
-
-    let cutoff = chrono::Utc::now() - chrono::Duration::days(INACTIVE_DAYS);
+    let now = chrono::Utc::now();
 
     for (guild_id, config) in connected_configured_guilds(ctx) {
+        // Members can't introduce themselves during the lockdown.
+        if lockdown::pauses_intros(ctx, config, guild_id).await? {
+            continue;
+        }
+
         let guild_name = guild_id
             .name(ctx.serenity())
             .context("Guild not available in cache")?;
 
-        let message = format!(
-            "You were kicked from {guild_name} because you did not submit an introduction. \
-            You can join again using an invite link."
-        );
+        let message = tr!(ctx, config.guild_locales(), "kick-dm", guild = guild_name,);
+        let reason = tr!(ctx, config.guild_locales(), "kick-reason");
 
-        guild_id
-            .members_iter(ctx.serenity())
-            .err_into::<Error>()
-            .try_filter(|member| future::ready(!member.user.bot))
-            .try_for_each(|member| {
-                // Needed to satisfy the borrow checker.
-                let message = message.as_str();
-
-                async move {
-                    let quarantined = member.roles.contains(&config.quarantine_role);
-                    let old = member
-                        .joined_at
-                        .is_some_and(|joined_at| *joined_at < cutoff);
-
-                    if quarantined && old {
-                        let dm_channel = member.user.create_dm_channel(ctx.serenity()).await?;
-                        dm_channel.say(ctx.serenity(), message).await?;
-
-                        member.kick_with_reason(ctx.serenity(), REASON).await?;
-                    }
-
-                    Ok(())
-                }
-            })
-            .await?;
+        let states = persist::state::get_all(ctx.db(), guild_id).await?;
+        let intros_resumed_at = lockdown::intros_resumed_at(ctx, config, guild_id).await?;
+
+        for member in get_members(ctx, guild_id).await? {
+            let state = states.get(&member.user.id).map(|&(state, _)| state);
+            if !needs_kick(ctx, config, &member, state, intros_resumed_at, now).await? {
+                continue;
+            }
+
+            let dm_channel = member.user.create_dm_channel(ctx.serenity()).await?;
+            dm_channel.say(ctx.serenity(), &message).await?;
+
+            member.kick_with_reason(ctx.serenity(), &reason).await?;
+
+            state::transition(ctx, guild_id, member.user.id, State::Kicked).await?;
+        }
     }
 
     Ok(())
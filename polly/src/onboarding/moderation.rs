@@ -0,0 +1,251 @@
+use anyhow::Context as _;
+use poise::CreateReply;
+use serenity::all::{Member, Mentionable};
+use tracing::info;
+
+use super::{age, intro, quarantine, report, review, rules};
+use crate::{PoiseApplicationContext, PoiseContext, context::Context, error::Result, l10n::tr};
+
+/// Lets only members with the mod role use the command, if one is configured.
+async fn has_mod_role(ctx: PoiseContext<'_>) -> Result<bool> {
+    let guild_id = ctx.guild_id().context("Context has no guild_id")?;
+    let config = ctx.config().guild(guild_id)?;
+
+    let Some(mod_role) = config.mod_role else {
+        return Ok(true);
+    };
+
+    let allowed = ctx
+        .author_member()
+        .await
+        .is_some_and(|member| member.roles.contains(&mod_role));
+
+    if !allowed {
+        let locales = config.user_locales(ctx.locale().unwrap_or_default());
+        ctx.send(
+            CreateReply::default()
+                .content(tr!(
+                    &ctx,
+                    locales,
+                    "moderation-not-allowed",
+                    role = mod_role.mention().to_string(),
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+    }
+
+    Ok(allowed)
+}
+
+/// The reason given by the moderator, for reports.
+fn reason_text(ctx: PoiseApplicationContext<'_>, locales: &[&str], reason: Option<&str>) -> String {
+    reason.map_or_else(|| tr!(&ctx, locales, "moderation-no-reason"), str::to_owned)
+}
+
+/// Quarantine a member, so that they have to introduce themselves
+#[poise::command(
+    guild_only,
+    slash_command,
+    rename = "quarantine",
+    check = "has_mod_role"
+)]
+#[tracing::instrument(
+    fields(
+        ctx.id = ctx.id(),
+        ctx.guild_id = %ctx.guild_id().unwrap_or_default(),
+        ctx.author.id = %ctx.author().id,
+        %member.user.id,
+    ),
+    skip(ctx, member),
+)]
+pub async fn quarantine_member(
+    ctx: PoiseApplicationContext<'_>,
+    #[description = "Member to quarantine"] member: Member,
+    #[description = "Why the member is quarantined, for the other moderators"] reason: Option<
+        String,
+    >,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let config = ctx.config().guild(member.guild_id)?;
+    let locales = config.user_locales(&ctx.interaction.locale);
+
+    if member.roles.contains(&config.quarantine_role) {
+        ctx.say(tr!(
+            &ctx,
+            locales,
+            "moderation-already-quarantined",
+            member = member.to_string(),
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    quarantine::quarantine(&ctx, &member).await?;
+
+    info!(reason, "Moderator quarantined member");
+    let guild_locales = config.guild_locales();
+    report(
+        &ctx,
+        config,
+        tr!(
+            &ctx,
+            guild_locales,
+            "moderation-quarantined-report",
+            moderator = ctx.author().mention().to_string(),
+            member = member.mention().to_string(),
+            tag = member.user.tag(),
+            reason = reason_text(ctx, &guild_locales, reason.as_deref()),
+        ),
+        "moderation.md",
+    )
+    .await?;
+
+    ctx.say(tr!(
+        &ctx,
+        locales,
+        "moderation-quarantined",
+        member = member.to_string(),
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Release a member from quarantine without an introduction
+#[poise::command(guild_only, slash_command, check = "has_mod_role")]
+#[tracing::instrument(
+    fields(
+        ctx.id = ctx.id(),
+        ctx.guild_id = %ctx.guild_id().unwrap_or_default(),
+        ctx.author.id = %ctx.author().id,
+        %member.user.id,
+    ),
+    skip(ctx, member),
+)]
+pub async fn release(
+    ctx: PoiseApplicationContext<'_>,
+    #[description = "Member to release"] mut member: Member,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let config = ctx.config().guild(member.guild_id)?;
+    let locales = config.user_locales(&ctx.interaction.locale);
+
+    let content = if !member.roles.contains(&config.quarantine_role) {
+        tr!(
+            &ctx,
+            locales,
+            "moderation-not-quarantined",
+            member = member.to_string(),
+        )
+    } else if !age::is_confirmed(&ctx, member.guild_id, member.user.id).await? {
+        tr!(
+            &ctx,
+            locales,
+            "moderation-age-not-confirmed",
+            member = member.to_string(),
+        )
+    } else if !rules::is_accepted(&ctx, config, member.guild_id, member.user.id).await? {
+        tr!(
+            &ctx,
+            locales,
+            "moderation-rules-not-accepted",
+            member = member.to_string(),
+        )
+    } else {
+        quarantine::unquarantine(&ctx, &mut member).await?;
+        review::cancel(&ctx, member.guild_id, member.user.id).await?;
+
+        info!("Moderator released member");
+        report(
+            &ctx,
+            config,
+            tr!(
+                &ctx,
+                config.guild_locales(),
+                "moderation-released-report",
+                moderator = ctx.author().mention().to_string(),
+                member = member.mention().to_string(),
+                tag = member.user.tag(),
+            ),
+            "moderation.md",
+        )
+        .await?;
+
+        tr!(
+            &ctx,
+            locales,
+            "moderation-released",
+            member = member.to_string(),
+        )
+    };
+
+    ctx.say(content).await?;
+
+    Ok(())
+}
+
+/// Delete a member's introduction and quarantine them, so that they have to introduce themselves again
+#[poise::command(
+    guild_only,
+    slash_command,
+    rename = "reset-intro",
+    check = "has_mod_role"
+)]
+#[tracing::instrument(
+    fields(
+        ctx.id = ctx.id(),
+        ctx.guild_id = %ctx.guild_id().unwrap_or_default(),
+        ctx.author.id = %ctx.author().id,
+        %member.user.id,
+    ),
+    skip(ctx, member),
+)]
+pub async fn reset_intro(
+    ctx: PoiseApplicationContext<'_>,
+    #[description = "Member whose introduction to delete"] member: Member,
+    #[description = "Why the introduction is deleted, for the other moderators"] reason: Option<
+        String,
+    >,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let config = ctx.config().guild(member.guild_id)?;
+    let locales = config.user_locales(&ctx.interaction.locale);
+
+    review::cancel(&ctx, member.guild_id, member.user.id).await?;
+    intro::reset(&ctx, member.guild_id, member.user.id).await?;
+
+    // Replaces the welcome message, which may say that the intro is awaiting review.
+    quarantine::quarantine(&ctx, &member).await?;
+
+    info!(reason, "Moderator reset intro");
+    let guild_locales = config.guild_locales();
+    report(
+        &ctx,
+        config,
+        tr!(
+            &ctx,
+            guild_locales,
+            "moderation-reset-intro-report",
+            moderator = ctx.author().mention().to_string(),
+            member = member.mention().to_string(),
+            tag = member.user.tag(),
+            reason = reason_text(ctx, &guild_locales, reason.as_deref()),
+        ),
+        "moderation.md",
+    )
+    .await?;
+
+    ctx.say(tr!(
+        &ctx,
+        locales,
+        "moderation-reset-intro",
+        member = member.to_string(),
+    ))
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use std::time::Duration;
+
+use poise::CreateReply;
+use serenity::all::{
+    ButtonStyle,
+    ComponentInteractionCollector,
+    CreateActionRow,
+    CreateAttachment,
+    CreateButton,
+    CreateInteractionResponse,
+    CreateInteractionResponseMessage,
+    CreateMessage,
+    GuildId,
+    UserId,
+};
+use tracing::info;
+
+use super::{
+    intro,
+    persist,
+    quarantine::delete_welcome_message,
+    reminders::delete_reminders,
+    review,
+};
+use crate::{
+    PoiseApplicationContext,
+    context::Context,
+    error::Result,
+    l10n::{self, tr},
+};
+
+/// How long the member has to confirm the deletion.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// These commands also work in DMs, where there is no guild locale.
+fn user_locales(ctx: PoiseApplicationContext<'_>) -> [&str; 2] {
+    let guild_locale = ctx
+        .guild_id()
+        .and_then(|guild_id| ctx.data.config.guilds.get(&guild_id))
+        .map_or(l10n::FALLBACK_LOCALE, |config| config.locale.as_str());
+
+    [ctx.interaction.locale.as_str(), guild_locale]
+}
+
+/// See and delete what Polly stores about you
+#[poise::command(slash_command, subcommand_required, subcommands("export", "delete"))]
+#[allow(clippy::unused_async)] // Never called, because a subcommand is required.
+pub async fn mydata(_ctx: PoiseApplicationContext<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Get a file with everything Polly stores about you
+#[poise::command(slash_command)]
+#[tracing::instrument(
+    fields(
+        ctx.id = ctx.id(),
+        ctx.author.id = %ctx.author().id,
+    ),
+    skip(ctx),
+)]
+pub async fn export(ctx: PoiseApplicationContext<'_>) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let locales = user_locales(ctx);
+    let json = persist::user_data::export(ctx.db(), ctx.author().id).await?;
+
+    let dm_result = async {
+        let dm_channel = ctx
+            .author()
+            .create_dm_channel(ctx.serenity_context())
+            .await?;
+        dm_channel
+            .send_message(
+                ctx.serenity_context(),
+                CreateMessage::new()
+                    .content(tr!(&ctx, locales, "mydata-export-dm"))
+                    .add_file(CreateAttachment::bytes(json, "polly_data.json")),
+            )
+            .await
+    }
+    .await;
+
+    let content = match dm_result {
+        Ok(_) => tr!(&ctx, locales, "mydata-export-sent"),
+        Err(err) => {
+            tracing::warn!(error = ?err, "Could not DM member");
+            tr!(&ctx, locales, "mydata-export-dm-failed")
+        }
+    };
+
+    ctx.say(content).await?;
+
+    Ok(())
+}
+
+/// Deletes the messages that belong to the member's data.
+async fn delete_messages(ctx: &impl Context, guild_id: GuildId, user_id: UserId) -> Result<()> {
+    delete_reminders(ctx, guild_id, user_id).await?;
+    delete_welcome_message(ctx, guild_id, user_id).await?;
+
+    review::cancel(ctx, guild_id, user_id).await?;
+
+    let mut tx = ctx.db().begin().await?;
+    intro::unpublish(ctx, &mut tx, guild_id, user_id).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Delete your introduction and everything else Polly stores about you
+#[poise::command(slash_command)]
+#[tracing::instrument(
+    fields(
+        ctx.id = ctx.id(),
+        ctx.author.id = %ctx.author().id,
+    ),
+    skip(ctx),
+)]
+pub async fn delete(ctx: PoiseApplicationContext<'_>) -> Result<()> {
+    let locales = user_locales(ctx);
+    let user_id = ctx.author().id;
+
+    let confirm_id = format!("{}confirm", ctx.id());
+    let cancel_id = format!("{}cancel", ctx.id());
+
+    let reply = ctx
+        .send(
+            CreateReply::default()
+                .content(tr!(&ctx, locales, "mydata-delete-confirm"))
+                .components(vec![CreateActionRow::Buttons(vec![
+                    CreateButton::new(&confirm_id)
+                        .label(tr!(&ctx, locales, "mydata-delete-button"))
+                        .style(ButtonStyle::Danger),
+                    CreateButton::new(&cancel_id)
+                        .label(tr!(&ctx, locales, "mydata-cancel-button"))
+                        .style(ButtonStyle::Secondary),
+                ])])
+                .ephemeral(true),
+        )
+        .await?;
+
+    let ctx_id = ctx.id().to_string();
+    let press = ComponentInteractionCollector::new(ctx.serenity_context())
+        .filter(move |press| press.data.custom_id.starts_with(&ctx_id))
+        .timeout(CONFIRM_TIMEOUT)
+        .await;
+
+    let Some(press) = press.filter(|press| press.data.custom_id == confirm_id) else {
+        reply
+            .edit(
+                poise::Context::Application(ctx),
+                CreateReply::default()
+                    .content(tr!(&ctx, locales, "mydata-delete-cancelled"))
+                    .components(vec![]),
+            )
+            .await?;
+        return Ok(());
+    };
+
+    press
+        .create_response(
+            ctx.serenity_context(),
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(tr!(&ctx, locales, "mydata-delete-in-progress"))
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+
+    for &guild_id in ctx.config().guilds.keys() {
+        delete_messages(&ctx, guild_id, user_id).await?;
+    }
+
+    let mut tx = ctx.db().begin().await?;
+    persist::user_data::delete(&mut tx, user_id).await?;
+    tx.commit().await?;
+
+    info!(%user_id, "Deleted user data");
+
+    reply
+        .edit(
+            poise::Context::Application(ctx),
+            CreateReply::default().content(tr!(&ctx, locales, "mydata-deleted")),
+        )
+        .await?;
+
+    Ok(())
+}
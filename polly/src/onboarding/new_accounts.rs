@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use serenity::all::{GuildId, Member, User};
+
+use super::{format_duration, persist, report};
+use crate::{config::GuildConfig, context::Context, error::Result, l10n::tr, template::Template};
+
+/// How old the account is, if it's too new for the guild.
+pub fn get_age(config: &GuildConfig, user: &User) -> Option<Duration> {
+    let new_accounts_config = config.new_accounts.as_ref()?;
+
+    let age = (chrono::Utc::now() - *user.id.created_at())
+        .to_std()
+        .unwrap_or_default();
+
+    (age < new_accounts_config.min_age).then_some(age)
+}
+
+pub fn is_new(config: &GuildConfig, user: &User) -> bool {
+    get_age(config, user).is_some()
+}
+
+/// Whether the account was too new for the guild when the member was welcomed.
+/// Their welcome message told them that their intro is held for review then.
+pub async fn was_new(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_id: GuildId,
+    user: &User,
+) -> Result<bool> {
+    let new_account = persist::state::get_new_account(ctx.db(), guild_id, user.id).await?;
+
+    Ok(new_account.unwrap_or_else(|| is_new(config, user)))
+}
+
+pub fn welcome_template(config: &GuildConfig, new_account: bool) -> &Template {
+    match &config.new_accounts {
+        Some(new_accounts_config) if new_account => &new_accounts_config.welcome_template,
+        _ => &config.welcome_template,
+    }
+}
+
+/// Tells the moderators that a new account joined.
+pub async fn report_joined(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    member: &Member,
+    age: Duration,
+) -> Result<()> {
+    let locales = config.guild_locales();
+    let text = tr!(
+        ctx,
+        locales,
+        "new-account-report",
+        member = member.to_string(),
+        tag = member.user.tag(),
+        age = format_duration(ctx, &locales, age),
+    );
+
+    report(ctx, config, text, "new_account.md").await
+}
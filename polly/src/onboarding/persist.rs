@@ -98,24 +98,1640 @@ macro_rules! message_cache_impl {
         }
     };
 
+    (delete_by_messages: $query:literal) => {
+        /// Deletes the rows of the messages and returns whose they were.
+        #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+        #[::tracing::instrument(skip(db))]
+        pub async fn delete_by_messages<'db, DB: ::sqlx::PgExecutor<'db>>(
+            db: DB,
+            guild_id: ::serenity::all::GuildId,
+            message_ids: &[::serenity::all::MessageId],
+        ) -> $crate::error::Result<Vec<::serenity::all::UserId>> {
+            let message_ids: Vec<i64> = message_ids
+                .iter()
+                .map(|message_id| message_id.get() as i64)
+                .collect();
+
+            let user_ids = ::sqlx::query!($query, guild_id.get() as i64, &message_ids)
+                .map(|record| ::serenity::all::UserId::new(record.user_id as u64))
+                .fetch_all(db)
+                .await?;
+
+            Ok(user_ids)
+        }
+    };
+
     ($($op:ident: $query:literal),* $(,)?) => {
         $(message_cache_impl! { $op: $query })*
     };
 }
 
 pub mod welcome_message {
+    use chrono::{DateTime, Utc};
+    use serenity::all::{ChannelId, GuildId, MessageId, UserId};
+    use sqlx::PgExecutor;
+
+    use crate::error::Result;
+
     message_cache_impl! {
-        set: "insert into onboarding_welcome_messages (guild_id, user_id, channel_id, message_id) values ($1, $2, $3, $4)",
         get: "select channel_id, message_id from onboarding_welcome_messages where guild_id = $1 and user_id = $2",
-        delete: "delete from onboarding_welcome_messages where guild_id = $1 and user_id = $2",
+        delete_by_messages: "delete from onboarding_welcome_messages where guild_id = $1 and message_id = any($2) returning user_id",
+    }
+
+    /// `thread` is whether `channel_id` is a thread created for the member.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn set<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        thread: bool,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query!(
+            "insert into onboarding_welcome_messages (guild_id, user_id, channel_id, message_id, thread, sent_at) values ($1, $2, $3, $4, $5, $6)
+            on conflict (guild_id, user_id) do update set channel_id = excluded.channel_id, message_id = excluded.message_id, thread = excluded.thread, sent_at = excluded.sent_at",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            channel_id.get() as i64,
+            message_id.get() as i64,
+            thread,
+            at,
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Deletes the member's welcome message, and returns it with whether it's in a thread
+    /// created for the member.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    #[tracing::instrument(skip(db))]
+    pub async fn take<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<(ChannelId, MessageId, bool)>> {
+        let welcome_message = sqlx::query!(
+            "delete from onboarding_welcome_messages where guild_id = $1 and user_id = $2 returning channel_id, message_id, thread",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .map(|record| {
+            (
+                ChannelId::new(record.channel_id as u64),
+                MessageId::new(record.message_id as u64),
+                record.thread,
+            )
+        })
+        .fetch_optional(db)
+        .await?;
+
+        Ok(welcome_message)
+    }
+
+    /// Returns the member's welcome thread, if they were welcomed in one.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get_thread<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<ChannelId>> {
+        let thread_id = sqlx::query!(
+            "select channel_id from onboarding_welcome_messages where guild_id = $1 and user_id = $2 and thread",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .map(|record| ChannelId::new(record.channel_id as u64))
+        .fetch_optional(db)
+        .await?;
+
+        Ok(thread_id)
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get_sent_at<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let sent_at = sqlx::query!(
+            "select sent_at from onboarding_welcome_messages where guild_id = $1 and user_id = $2",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .map(|record| record.sent_at)
+        .fetch_optional(db)
+        .await?
+        .flatten();
+
+        Ok(sent_at)
     }
 }
 
 pub mod intro_message {
     message_cache_impl! {
-        set: "insert into onboarding_intro_messages (guild_id, user_id, channel_id, message_id) values ($1, $2, $3, $4)",
+        set: "insert into onboarding_intro_messages (guild_id, user_id, channel_id, message_id) values ($1, $2, $3, $4)
+        on conflict (guild_id, user_id) do update set channel_id = excluded.channel_id, message_id = excluded.message_id",
         get: "select channel_id, message_id from onboarding_intro_messages where guild_id = $1 and user_id = $2",
         get_all: "select user_id, channel_id, message_id from onboarding_intro_messages where guild_id = $1",
         delete: "delete from onboarding_intro_messages where guild_id = $1 and user_id = $2",
+        delete_by_messages: "delete from onboarding_intro_messages where guild_id = $1 and message_id = any($2) returning user_id",
+    }
+}
+
+pub mod intro {
+    use std::collections::BTreeMap;
+
+    use chrono::{DateTime, Utc};
+    use serenity::all::{GuildId, UserId};
+    use sqlx::{Connection, PgConnection, PgExecutor, types::Json};
+
+    use crate::error::Result;
+
+    /// Inserts or replaces the intro, keeping the original submission time, and unarchives it.
+    /// Records a new revision if the fields changed.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db, fields))]
+    pub async fn set<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+        fields: &BTreeMap<String, String>,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query!(
+            "with revision as (\
+                insert into onboarding_intro_revisions (guild_id, user_id, fields, created_at) select $1, $2, $3, $4 \
+                where not exists (select from onboarding_intros where guild_id = $1 and user_id = $2 and fields = $3)\
+            ) \
+            insert into onboarding_intros (guild_id, user_id, fields, submitted_at, updated_at) values ($1, $2, $3, $4, $4) \
+            on conflict (guild_id, user_id) do update set fields = excluded.fields, updated_at = excluded.updated_at, archived_at = null",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            Json(fields) as _,
+            at,
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<BTreeMap<String, String>>> {
+        let fields = sqlx::query!(
+            r#"select fields as "fields: Json<BTreeMap<String, String>>" from onboarding_intros where guild_id = $1 and user_id = $2 and archived_at is null"#,
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .map(|record| record.fields.0)
+        .fetch_optional(db)
+        .await?;
+
+        Ok(fields)
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get_archived<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<BTreeMap<String, String>>> {
+        let fields = sqlx::query!(
+            r#"select fields as "fields: Json<BTreeMap<String, String>>" from onboarding_intros where guild_id = $1 and user_id = $2 and archived_at is not null"#,
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .map(|record| record.fields.0)
+        .fetch_optional(db)
+        .await?;
+
+        Ok(fields)
+    }
+
+    /// Deletes the intro, archived or not. Its revisions are kept.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn delete<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<()> {
+        sqlx::query!(
+            "delete from onboarding_intros where guild_id = $1 and user_id = $2",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the other member whose published intro is the most similar to the text,
+    /// if the trigram similarity is above the threshold.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss
+    )]
+    #[tracing::instrument(skip(conn, text))]
+    pub async fn find_similar(
+        conn: &mut PgConnection,
+        guild_id: GuildId,
+        user_id: UserId,
+        text: &str,
+        threshold: f64,
+    ) -> Result<Option<UserId>> {
+        let mut tx = conn.begin().await?;
+
+        // Lets the % operator use the index to find candidates.
+        sqlx::query!(
+            "select set_config('pg_trgm.similarity_threshold', $1, true)",
+            threshold.to_string(),
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let similar = sqlx::query_scalar!(
+            "select user_id from onboarding_intros \
+            where guild_id = $1 and user_id != $2 and archived_at is null \
+            and onboarding_intro_text(fields) % $3 and similarity(onboarding_intro_text(fields), $3) > $4 \
+            order by similarity(onboarding_intro_text(fields), $3) desc limit 1",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            text,
+            threshold as f32,
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .map(|user_id| UserId::new(user_id as u64));
+
+        tx.commit().await?;
+
+        Ok(similar)
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn archive<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query!(
+            "update onboarding_intros set archived_at = $3 where guild_id = $1 and user_id = $2",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            at,
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the authors of intros matching the query, best matches first.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    #[tracing::instrument(skip(db))]
+    pub async fn search<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        query: &str,
+    ) -> Result<Vec<UserId>> {
+        let user_ids = sqlx::query!(
+            "select user_id from onboarding_intros, websearch_to_tsquery('simple', $2) as query \
+            where guild_id = $1 and archived_at is null and to_tsvector('simple', fields) @@ query \
+            order by ts_rank(to_tsvector('simple', fields), query) desc, user_id",
+            guild_id.get() as i64,
+            query,
+        )
+        .map(|record| UserId::new(record.user_id as u64))
+        .fetch_all(db)
+        .await?;
+
+        Ok(user_ids)
+    }
+}
+
+pub mod intro_revision {
+    use std::collections::BTreeMap;
+
+    use chrono::{DateTime, Utc};
+    use serenity::all::{GuildId, UserId};
+    use sqlx::{PgExecutor, types::Json};
+
+    use crate::error::Result;
+
+    pub struct Revision {
+        pub fields: BTreeMap<String, String>,
+        pub created_at: DateTime<Utc>,
+    }
+
+    /// Returns all revisions of the intro, oldest first.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get_all<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<Revision>> {
+        let revisions = sqlx::query!(
+            r#"select fields as "fields: Json<BTreeMap<String, String>>", created_at from onboarding_intro_revisions where guild_id = $1 and user_id = $2 order by created_at, id"#,
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .map(|record| Revision {
+            fields: record.fields.0,
+            created_at: record.created_at,
+        })
+        .fetch_all(db)
+        .await?;
+
+        Ok(revisions)
+    }
+}
+
+pub mod intro_review {
+    use std::collections::BTreeMap;
+
+    use chrono::{DateTime, Utc};
+    use serenity::all::{ChannelId, GuildId, MessageId, UserId};
+    use sqlx::{PgExecutor, types::Json};
+
+    use crate::error::{Result, bail};
+
+    pub struct Review {
+        pub fields: BTreeMap<String, String>,
+        pub channel_id: ChannelId,
+        pub message_id: MessageId,
+        pub changes_requested: bool,
+    }
+
+    /// Inserts or replaces the pending review of the intro.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db, fields))]
+    pub async fn set<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+        fields: &BTreeMap<String, String>,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query!(
+            "insert into onboarding_intro_reviews (guild_id, user_id, fields, channel_id, message_id, submitted_at) values ($1, $2, $3, $4, $5, $6) \
+            on conflict (guild_id, user_id) do update set fields = excluded.fields, channel_id = excluded.channel_id, message_id = excluded.message_id, \
+            changes_requested = false, submitted_at = excluded.submitted_at",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            Json(fields) as _,
+            channel_id.get() as i64,
+            message_id.get() as i64,
+            at,
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<Review>> {
+        let review = sqlx::query!(
+            r#"select fields as "fields: Json<BTreeMap<String, String>>", channel_id, message_id, changes_requested from onboarding_intro_reviews where guild_id = $1 and user_id = $2"#,
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .map(|record| Review {
+            fields: record.fields.0,
+            channel_id: ChannelId::new(record.channel_id as u64),
+            message_id: MessageId::new(record.message_id as u64),
+            changes_requested: record.changes_requested,
+        })
+        .fetch_optional(db)
+        .await?;
+
+        Ok(review)
+    }
+
+    /// Keeps the submitted intro so that the member can edit it, but marks it as no longer pending.
+    /// Returns false if the review posted as the message was no longer pending, so that only one
+    /// moderator handles it.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn request_changes<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+        message_id: MessageId,
+    ) -> Result<bool> {
+        let query_result = sqlx::query!(
+            "update onboarding_intro_reviews set changes_requested = true \
+            where guild_id = $1 and user_id = $2 and message_id = $3 and not changes_requested",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            message_id.get() as i64,
+        )
+        .execute(db)
+        .await?;
+
+        Ok(query_result.rows_affected() > 0)
+    }
+
+    /// Deletes the review posted as the message if it is still pending, and returns it.
+    /// Returns `None` if it was already handled, so that only one moderator handles it.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    #[tracing::instrument(skip(db))]
+    pub async fn take<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+        message_id: MessageId,
+    ) -> Result<Option<Review>> {
+        let review = sqlx::query!(
+            r#"delete from onboarding_intro_reviews where guild_id = $1 and user_id = $2 and message_id = $3 and not changes_requested
+            returning fields as "fields: Json<BTreeMap<String, String>>", channel_id, message_id, changes_requested"#,
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            message_id.get() as i64,
+        )
+        .map(|record| Review {
+            fields: record.fields.0,
+            channel_id: ChannelId::new(record.channel_id as u64),
+            message_id: MessageId::new(record.message_id as u64),
+            changes_requested: record.changes_requested,
+        })
+        .fetch_optional(db)
+        .await?;
+
+        Ok(review)
+    }
+
+    /// Returns the members whose intro is awaiting review.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get_all_pending<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+    ) -> Result<Vec<UserId>> {
+        let user_ids = sqlx::query!(
+            "select user_id from onboarding_intro_reviews where guild_id = $1 and not changes_requested",
+            guild_id.get() as i64,
+        )
+        .map(|record| UserId::new(record.user_id as u64))
+        .fetch_all(db)
+        .await?;
+
+        Ok(user_ids)
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn delete<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<()> {
+        let query_result = sqlx::query!(
+            "delete from onboarding_intro_reviews where guild_id = $1 and user_id = $2",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .execute(db)
+        .await?;
+
+        if query_result.rows_affected() == 0 {
+            bail!("No rows deleted")
+        }
+
+        Ok(())
+    }
+}
+
+pub mod reminder {
+    use std::time::Duration;
+
+    use chrono::{DateTime, Utc};
+    use serenity::all::{ChannelId, GuildId, MessageId, UserId};
+    use sqlx::PgExecutor;
+
+    use crate::error::Result;
+
+    pub struct Reminder {
+        pub offset: Duration,
+        pub channel_id: ChannelId,
+        pub message_id: MessageId,
+        pub sent_at: DateTime<Utc>,
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn add<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+        offset: Duration,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query!(
+            "insert into onboarding_reminders (guild_id, user_id, offset_secs, channel_id, message_id, sent_at) values ($1, $2, $3, $4, $5, $6)",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            offset.as_secs() as i64,
+            channel_id.get() as i64,
+            message_id.get() as i64,
+            at,
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get_all<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<Reminder>> {
+        let reminders = sqlx::query!(
+            "select offset_secs, channel_id, message_id, sent_at from onboarding_reminders where guild_id = $1 and user_id = $2",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .map(|record| Reminder {
+            offset: Duration::from_secs(record.offset_secs as u64),
+            channel_id: ChannelId::new(record.channel_id as u64),
+            message_id: MessageId::new(record.message_id as u64),
+            sent_at: record.sent_at,
+        })
+        .fetch_all(db)
+        .await?;
+
+        Ok(reminders)
+    }
+
+    /// Deletes the member's reminders, and returns them.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    #[tracing::instrument(skip(db))]
+    pub async fn take_all<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<Reminder>> {
+        let reminders = sqlx::query!(
+            "delete from onboarding_reminders where guild_id = $1 and user_id = $2 returning offset_secs, channel_id, message_id, sent_at",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .map(|record| Reminder {
+            offset: Duration::from_secs(record.offset_secs as u64),
+            channel_id: ChannelId::new(record.channel_id as u64),
+            message_id: MessageId::new(record.message_id as u64),
+            sent_at: record.sent_at,
+        })
+        .fetch_all(db)
+        .await?;
+
+        Ok(reminders)
+    }
+}
+
+pub mod age_confirmation {
+    use chrono::{DateTime, Utc};
+    use serenity::all::{GuildId, UserId};
+    use sqlx::PgExecutor;
+
+    use crate::error::Result;
+
+    /// Records the confirmation, unless the member already confirmed before.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn set<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query!(
+            "insert into onboarding_age_confirmations (guild_id, user_id, confirmed_at) values ($1, $2, $3) \
+            on conflict (guild_id, user_id) do nothing",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            at,
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let confirmed_at = sqlx::query!(
+            "select confirmed_at from onboarding_age_confirmations where guild_id = $1 and user_id = $2",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .map(|record| record.confirmed_at)
+        .fetch_optional(db)
+        .await?;
+
+        Ok(confirmed_at)
+    }
+}
+
+pub mod rules_version {
+    use chrono::{DateTime, Utc};
+    use serenity::all::GuildId;
+    use sqlx::PgExecutor;
+
+    use crate::error::Result;
+
+    pub struct RulesVersion {
+        pub published_at: DateTime<Utc>,
+        pub reported_at: Option<DateTime<Utc>>,
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn add<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        version: i32,
+        published_at: DateTime<Utc>,
+        reported_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        sqlx::query!(
+            "insert into onboarding_rules_versions (guild_id, version, published_at, reported_at) values ($1, $2, $3, $4)",
+            guild_id.get() as i64,
+            version,
+            published_at,
+            reported_at,
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        version: i32,
+    ) -> Result<Option<RulesVersion>> {
+        let rules_version = sqlx::query!(
+            "select published_at, reported_at from onboarding_rules_versions where guild_id = $1 and version = $2",
+            guild_id.get() as i64,
+            version,
+        )
+        .map(|record| RulesVersion {
+            published_at: record.published_at,
+            reported_at: record.reported_at,
+        })
+        .fetch_optional(db)
+        .await?;
+
+        Ok(rules_version)
+    }
+
+    /// Whether any version of the guild's rules has been published before.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn any<'db, DB: PgExecutor<'db>>(db: DB, guild_id: GuildId) -> Result<bool> {
+        let any = sqlx::query!(
+            r#"select exists (select from onboarding_rules_versions where guild_id = $1) as "any!""#,
+            guild_id.get() as i64,
+        )
+        .map(|record| record.any)
+        .fetch_one(db)
+        .await?;
+
+        Ok(any)
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn set_reported<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        version: i32,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query!(
+            "update onboarding_rules_versions set reported_at = $3 where guild_id = $1 and version = $2",
+            guild_id.get() as i64,
+            version,
+            at,
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+}
+
+pub mod rules_acceptance {
+    use std::collections::HashSet;
+
+    use chrono::{DateTime, Utc};
+    use serenity::all::{GuildId, UserId};
+    use sqlx::PgExecutor;
+
+    use crate::error::Result;
+
+    /// Records the acceptance, unless the member already agreed to this version before.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn set<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+        version: i32,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query!(
+            "insert into onboarding_rules_acceptances (guild_id, user_id, version, accepted_at) values ($1, $2, $3, $4) \
+            on conflict (guild_id, user_id, version) do nothing",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            version,
+            at,
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+        version: i32,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let accepted_at = sqlx::query!(
+            "select accepted_at from onboarding_rules_acceptances where guild_id = $1 and user_id = $2 and version = $3",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            version,
+        )
+        .map(|record| record.accepted_at)
+        .fetch_optional(db)
+        .await?;
+
+        Ok(accepted_at)
+    }
+
+    /// Returns the members who agreed to this version.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get_user_ids<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        version: i32,
+    ) -> Result<HashSet<UserId>> {
+        let user_ids = sqlx::query!(
+            "select user_id from onboarding_rules_acceptances where guild_id = $1 and version = $2",
+            guild_id.get() as i64,
+            version,
+        )
+        .map(|record| UserId::new(record.user_id as u64))
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+        Ok(user_ids)
+    }
+}
+
+pub mod lifecycle {
+    use chrono::{DateTime, Utc};
+    use serenity::all::{GuildId, UserId};
+    use sqlx::PgExecutor;
+
+    use crate::error::Result;
+
+    /// A step in onboarding after joining.
+    #[derive(Clone, Copy, Debug)]
+    pub enum Stage {
+        Welcomed,
+        IntroSubmitted,
+        Unquarantined,
+        Kicked,
+        Left,
+    }
+
+    /// Conversion of the members who joined in a week, or in the whole window if `week` is `None`.
+    pub struct Funnel {
+        pub week: Option<DateTime<Utc>>,
+        pub joined: i64,
+        pub welcomed: i64,
+        pub intro_submitted: i64,
+        pub unquarantined: i64,
+        pub kicked: i64,
+        /// Members who left before completing onboarding, without being kicked.
+        pub left: i64,
+        pub median_secs_to_intro: Option<i64>,
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn add<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+        joined_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query!(
+            "insert into onboarding_lifecycles (guild_id, user_id, joined_at) values ($1, $2, $3)",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            joined_at,
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records the stage for the member's latest join, unless it was already recorded.
+    /// Members who joined before lifecycles were recorded have no row, and are skipped.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn set<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+        stage: Stage,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        let guild_id = guild_id.get() as i64;
+        let user_id = user_id.get() as i64;
+
+        let query = match stage {
+            Stage::Welcomed => sqlx::query!(
+                "update onboarding_lifecycles set welcomed_at = coalesce(welcomed_at, $3) \
+                where id = (select id from onboarding_lifecycles where guild_id = $1 and user_id = $2 order by joined_at desc limit 1)",
+                guild_id,
+                user_id,
+                at,
+            ),
+            Stage::IntroSubmitted => sqlx::query!(
+                "update onboarding_lifecycles set intro_submitted_at = coalesce(intro_submitted_at, $3) \
+                where id = (select id from onboarding_lifecycles where guild_id = $1 and user_id = $2 order by joined_at desc limit 1)",
+                guild_id,
+                user_id,
+                at,
+            ),
+            Stage::Unquarantined => sqlx::query!(
+                "update onboarding_lifecycles set unquarantined_at = coalesce(unquarantined_at, $3) \
+                where id = (select id from onboarding_lifecycles where guild_id = $1 and user_id = $2 order by joined_at desc limit 1)",
+                guild_id,
+                user_id,
+                at,
+            ),
+            Stage::Kicked => sqlx::query!(
+                "update onboarding_lifecycles set kicked_at = coalesce(kicked_at, $3) \
+                where id = (select id from onboarding_lifecycles where guild_id = $1 and user_id = $2 order by joined_at desc limit 1)",
+                guild_id,
+                user_id,
+                at,
+            ),
+            Stage::Left => sqlx::query!(
+                "update onboarding_lifecycles set left_at = coalesce(left_at, $3) \
+                where id = (select id from onboarding_lifecycles where guild_id = $1 and user_id = $2 order by joined_at desc limit 1)",
+                guild_id,
+                user_id,
+                at,
+            ),
+        };
+
+        query.execute(db).await?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn count_joined_since<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        since: DateTime<Utc>,
+    ) -> Result<i64> {
+        let count = sqlx::query!(
+            r#"select count(*) as "count!" from onboarding_lifecycles where guild_id = $1 and joined_at >= $2"#,
+            guild_id.get() as i64,
+            since,
+        )
+        .map(|record| record.count)
+        .fetch_one(db)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Returns the funnel per week of joining, newest first, followed by the total.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get_funnels<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Funnel>> {
+        let funnels = sqlx::query!(
+            r#"select
+                date_trunc('week', joined_at) as "week?",
+                count(*) as "joined!",
+                count(welcomed_at) as "welcomed!",
+                count(intro_submitted_at) as "intro_submitted!",
+                count(unquarantined_at) as "unquarantined!",
+                count(kicked_at) as "kicked!",
+                count(left_at) filter (where unquarantined_at is null and kicked_at is null) as "left!",
+                round(percentile_cont(0.5) within group (
+                    order by extract(epoch from intro_submitted_at - joined_at)::float8
+                ))::bigint as median_secs_to_intro
+            from onboarding_lifecycles
+            where guild_id = $1 and joined_at >= $2
+            group by rollup (date_trunc('week', joined_at))
+            order by date_trunc('week', joined_at) desc nulls last"#,
+            guild_id.get() as i64,
+            since,
+        )
+        .map(|record| Funnel {
+            week: record.week,
+            joined: record.joined,
+            welcomed: record.welcomed,
+            intro_submitted: record.intro_submitted,
+            unquarantined: record.unquarantined,
+            kicked: record.kicked,
+            left: record.left,
+            median_secs_to_intro: record.median_secs_to_intro,
+        })
+        .fetch_all(db)
+        .await?;
+
+        Ok(funnels)
+    }
+}
+
+pub mod state {
+    use std::collections::HashMap;
+
+    use chrono::{DateTime, Utc};
+    use serenity::all::{GuildId, UserId};
+    use sqlx::{PgConnection, PgExecutor};
+
+    use crate::error::Result;
+
+    /// Where a member is in onboarding.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, sqlx::Type)]
+    #[sqlx(type_name = "onboarding_state", rename_all = "snake_case")]
+    pub enum State {
+        Joined,
+        Welcomed,
+        IntroSubmitted,
+        UnderReview,
+        Member,
+        Kicked,
+        Left,
+    }
+
+    pub struct Transition {
+        pub from: Option<State>,
+        pub to: State,
+        pub at: DateTime<Utc>,
+    }
+
+    /// Returns the state and when the member got into it.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<(State, DateTime<Utc>)>> {
+        let state = sqlx::query!(
+            r#"select state as "state: State", updated_at from onboarding_states where guild_id = $1 and user_id = $2"#,
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .map(|record| (record.state, record.updated_at))
+        .fetch_optional(db)
+        .await?;
+
+        Ok(state)
+    }
+
+    /// Returns the state, and locks it until the transaction ends so that it isn't changed
+    /// concurrently.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(conn))]
+    pub async fn get_for_update(
+        conn: &mut PgConnection,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<State>> {
+        let state = sqlx::query!(
+            r#"select state as "state: State" from onboarding_states where guild_id = $1 and user_id = $2 for update"#,
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .map(|record| record.state)
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(state)
+    }
+
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get_all<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+    ) -> Result<HashMap<UserId, (State, DateTime<Utc>)>> {
+        let states = sqlx::query!(
+            r#"select user_id, state as "state: State", updated_at from onboarding_states where guild_id = $1"#,
+            guild_id.get() as i64,
+        )
+        .map(|record| {
+            (
+                UserId::new(record.user_id as u64),
+                (record.state, record.updated_at),
+            )
+        })
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+        Ok(states)
+    }
+
+    /// Sets the state and records the transition.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(conn))]
+    pub async fn set(
+        conn: &mut PgConnection,
+        guild_id: GuildId,
+        user_id: UserId,
+        from: Option<State>,
+        to: State,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        let guild_id = guild_id.get() as i64;
+        let user_id = user_id.get() as i64;
+
+        sqlx::query!(
+            "insert into onboarding_states (guild_id, user_id, state, updated_at) values ($1, $2, $3, $4)
+            on conflict (guild_id, user_id) do update set state = excluded.state, updated_at = excluded.updated_at",
+            guild_id,
+            user_id,
+            to as State,
+            at,
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query!(
+            "insert into onboarding_state_transitions (guild_id, user_id, from_state, to_state, at) values ($1, $2, $3, $4, $5)",
+            guild_id,
+            user_id,
+            from as Option<State>,
+            to as State,
+            at,
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records whether the member's account was too new when they were welcomed.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn set_new_account<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+        new_account: bool,
+    ) -> Result<()> {
+        sqlx::query!(
+            "update onboarding_states set new_account = $3 where guild_id = $1 and user_id = $2",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            new_account,
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns whether the member's account was too new when they were welcomed,
+    /// unless they were welcomed before that was recorded.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get_new_account<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<bool>> {
+        let new_account = sqlx::query!(
+            "select new_account from onboarding_states where guild_id = $1 and user_id = $2",
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .map(|record| record.new_account)
+        .fetch_optional(db)
+        .await?
+        .flatten();
+
+        Ok(new_account)
+    }
+
+    /// Returns the transitions, oldest first.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get_transitions<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<Transition>> {
+        let transitions = sqlx::query!(
+            r#"select from_state as "from_state: State", to_state as "to_state: State", at
+            from onboarding_state_transitions
+            where guild_id = $1 and user_id = $2
+            order by at, id"#,
+            guild_id.get() as i64,
+            user_id.get() as i64,
+        )
+        .map(|record| Transition {
+            from: record.from_state,
+            to: record.to_state,
+            at: record.at,
+        })
+        .fetch_all(db)
+        .await?;
+
+        Ok(transitions)
+    }
+}
+
+pub mod lockdown {
+    use chrono::{DateTime, Utc};
+    use serenity::all::GuildId;
+    use sqlx::PgExecutor;
+
+    use crate::error::Result;
+
+    /// Starts a lockdown, unless one is already active. Returns whether it was started.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn start<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        at: DateTime<Utc>,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            "insert into onboarding_lockdowns (guild_id, started_at) values ($1, $2) \
+            on conflict (guild_id) where ended_at is null do nothing",
+            guild_id.get() as i64,
+            at,
+        )
+        .execute(db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns when the active lockdown started, if any.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get_active<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let started_at = sqlx::query!(
+            "select started_at from onboarding_lockdowns where guild_id = $1 and ended_at is null",
+            guild_id.get() as i64,
+        )
+        .map(|record| record.started_at)
+        .fetch_optional(db)
+        .await?;
+
+        Ok(started_at)
+    }
+
+    /// Returns when the last lockdown ended, if any.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn get_last_ended<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let ended_at = sqlx::query!(
+            "select max(ended_at) as ended_at from onboarding_lockdowns where guild_id = $1",
+            guild_id.get() as i64,
+        )
+        .map(|record| record.ended_at)
+        .fetch_one(db)
+        .await?;
+
+        Ok(ended_at)
+    }
+
+    /// Ends the active lockdown. Returns whether there was one.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn end<'db, DB: PgExecutor<'db>>(
+        db: DB,
+        guild_id: GuildId,
+        at: DateTime<Utc>,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            "update onboarding_lockdowns set ended_at = $2 where guild_id = $1 and ended_at is null",
+            guild_id.get() as i64,
+            at,
+        )
+        .execute(db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Everything stored about a user, across all guilds.
+/// Tables with user data must be added here, so that users can export and delete it.
+pub mod user_data {
+    use serenity::all::UserId;
+    use sqlx::{PgConnection, PgExecutor};
+
+    use crate::error::Result;
+
+    /// Returns the data as pretty-printed JSON, with one array of rows per table.
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(db))]
+    pub async fn export<'db, DB: PgExecutor<'db>>(db: DB, user_id: UserId) -> Result<String> {
+        let json = sqlx::query!(
+            r#"select jsonb_pretty(jsonb_build_object(
+                'age_confirmations', (select coalesce(jsonb_agg(to_jsonb(t)), '[]') from onboarding_age_confirmations t where user_id = $1),
+                'intro_messages', (select coalesce(jsonb_agg(to_jsonb(t)), '[]') from onboarding_intro_messages t where user_id = $1),
+                'intro_reviews', (select coalesce(jsonb_agg(to_jsonb(t)), '[]') from onboarding_intro_reviews t where user_id = $1),
+                'intro_revisions', (select coalesce(jsonb_agg(to_jsonb(t) order by t.created_at), '[]') from onboarding_intro_revisions t where user_id = $1),
+                'intros', (select coalesce(jsonb_agg(to_jsonb(t)), '[]') from onboarding_intros t where user_id = $1),
+                'lifecycles', (select coalesce(jsonb_agg(to_jsonb(t) order by t.joined_at), '[]') from onboarding_lifecycles t where user_id = $1),
+                'reminders', (select coalesce(jsonb_agg(to_jsonb(t)), '[]') from onboarding_reminders t where user_id = $1),
+                'rules_acceptances', (select coalesce(jsonb_agg(to_jsonb(t)), '[]') from onboarding_rules_acceptances t where user_id = $1),
+                'state_transitions', (select coalesce(jsonb_agg(to_jsonb(t) order by t.at), '[]') from onboarding_state_transitions t where user_id = $1),
+                'states', (select coalesce(jsonb_agg(to_jsonb(t)), '[]') from onboarding_states t where user_id = $1),
+                'welcome_messages', (select coalesce(jsonb_agg(to_jsonb(t)), '[]') from onboarding_welcome_messages t where user_id = $1)
+            )) as "json!""#,
+            user_id.get() as i64,
+        )
+        .map(|record| record.json)
+        .fetch_one(db)
+        .await?;
+
+        Ok(json)
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    #[tracing::instrument(skip(conn))]
+    pub async fn delete(conn: &mut PgConnection, user_id: UserId) -> Result<()> {
+        let user_id = user_id.get() as i64;
+
+        sqlx::query!(
+            "delete from onboarding_age_confirmations where user_id = $1",
+            user_id
+        )
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query!(
+            "delete from onboarding_intro_messages where user_id = $1",
+            user_id
+        )
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query!(
+            "delete from onboarding_intro_reviews where user_id = $1",
+            user_id
+        )
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query!(
+            "delete from onboarding_intro_revisions where user_id = $1",
+            user_id
+        )
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query!("delete from onboarding_intros where user_id = $1", user_id)
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query!(
+            "delete from onboarding_lifecycles where user_id = $1",
+            user_id
+        )
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query!(
+            "delete from onboarding_reminders where user_id = $1",
+            user_id
+        )
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query!(
+            "delete from onboarding_rules_acceptances where user_id = $1",
+            user_id
+        )
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query!(
+            "delete from onboarding_state_transitions where user_id = $1",
+            user_id
+        )
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query!("delete from onboarding_states where user_id = $1", user_id)
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query!(
+            "delete from onboarding_welcome_messages where user_id = $1",
+            user_id
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::{TimeDelta, Utc};
+    use serenity::all::{ChannelId, GuildId, MessageId, UserId};
+    use sqlx::PgPool;
+
+    use super::{
+        intro,
+        intro_message,
+        state::{self, State},
+        welcome_message,
+    };
+    use crate::error::Result;
+
+    const GUILD_ID: GuildId = GuildId::new(1);
+    const USER_ID: UserId = UserId::new(2);
+
+    #[sqlx::test]
+    async fn welcome_message_set_replaces_stale_row(db: PgPool) -> Result<()> {
+        let stale_sent_at = Utc::now() - TimeDelta::days(30);
+        welcome_message::set(
+            &db,
+            GUILD_ID,
+            USER_ID,
+            ChannelId::new(10),
+            MessageId::new(11),
+            true,
+            stale_sent_at,
+        )
+        .await?;
+
+        let sent_at = Utc::now();
+        welcome_message::set(
+            &db,
+            GUILD_ID,
+            USER_ID,
+            ChannelId::new(20),
+            MessageId::new(21),
+            false,
+            sent_at,
+        )
+        .await?;
+
+        assert_eq!(
+            welcome_message::get(&db, GUILD_ID, USER_ID).await?,
+            Some((ChannelId::new(20), MessageId::new(21))),
+        );
+        assert_eq!(
+            welcome_message::get_thread(&db, GUILD_ID, USER_ID).await?,
+            None,
+        );
+        // Postgres stores microseconds.
+        assert_eq!(
+            welcome_message::get_sent_at(&db, GUILD_ID, USER_ID)
+                .await?
+                .map(|at| at.timestamp_micros()),
+            Some(sent_at.timestamp_micros()),
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn welcome_message_can_be_set_again_after_delete(db: PgPool) -> Result<()> {
+        let channel_id = ChannelId::new(10);
+
+        welcome_message::set(
+            &db,
+            GUILD_ID,
+            USER_ID,
+            channel_id,
+            MessageId::new(11),
+            false,
+            Utc::now(),
+        )
+        .await?;
+        assert_eq!(
+            welcome_message::take(&db, GUILD_ID, USER_ID).await?,
+            Some((channel_id, MessageId::new(11), false)),
+        );
+        assert_eq!(welcome_message::take(&db, GUILD_ID, USER_ID).await?, None);
+        assert_eq!(welcome_message::get(&db, GUILD_ID, USER_ID).await?, None);
+
+        welcome_message::set(
+            &db,
+            GUILD_ID,
+            USER_ID,
+            channel_id,
+            MessageId::new(12),
+            false,
+            Utc::now(),
+        )
+        .await?;
+        assert_eq!(
+            welcome_message::get(&db, GUILD_ID, USER_ID).await?,
+            Some((channel_id, MessageId::new(12))),
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn intro_message_set_replaces_stale_row(db: PgPool) -> Result<()> {
+        intro_message::set(
+            &db,
+            GUILD_ID,
+            USER_ID,
+            ChannelId::new(10),
+            MessageId::new(11),
+        )
+        .await?;
+        intro_message::set(
+            &db,
+            GUILD_ID,
+            USER_ID,
+            ChannelId::new(20),
+            MessageId::new(21),
+        )
+        .await?;
+
+        assert_eq!(
+            intro_message::get(&db, GUILD_ID, USER_ID).await?,
+            Some((ChannelId::new(20), MessageId::new(21))),
+        );
+        assert_eq!(intro_message::get_all(&db, GUILD_ID).await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn delete_by_messages_only_deletes_those_messages(db: PgPool) -> Result<()> {
+        let other_user_id = UserId::new(3);
+        let channel_id = ChannelId::new(10);
+
+        intro_message::set(&db, GUILD_ID, USER_ID, channel_id, MessageId::new(11)).await?;
+        intro_message::set(&db, GUILD_ID, other_user_id, channel_id, MessageId::new(12)).await?;
+
+        let deleted =
+            intro_message::delete_by_messages(&db, GUILD_ID, &[MessageId::new(11)]).await?;
+        assert_eq!(deleted, vec![USER_ID]);
+
+        // Deleting again finds nothing, like the event of a message Polly deleted itself.
+        let deleted =
+            intro_message::delete_by_messages(&db, GUILD_ID, &[MessageId::new(11)]).await?;
+        assert!(deleted.is_empty());
+
+        assert_eq!(
+            intro_message::get(&db, GUILD_ID, other_user_id).await?,
+            Some((channel_id, MessageId::new(12))),
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn state_set_records_transitions(db: PgPool) -> Result<()> {
+        let joined_at = Utc::now() - TimeDelta::hours(1);
+        let welcomed_at = Utc::now();
+
+        let mut conn = db.acquire().await?;
+        state::set(&mut conn, GUILD_ID, USER_ID, None, State::Joined, joined_at).await?;
+        state::set(
+            &mut conn,
+            GUILD_ID,
+            USER_ID,
+            Some(State::Joined),
+            State::Welcomed,
+            welcomed_at,
+        )
+        .await?;
+
+        let (current, updated_at) = state::get(&db, GUILD_ID, USER_ID).await?.unwrap();
+        assert_eq!(current, State::Welcomed);
+        assert_eq!(
+            updated_at.timestamp_micros(),
+            welcomed_at.timestamp_micros()
+        );
+
+        let transitions: Vec<_> = state::get_transitions(&db, GUILD_ID, USER_ID)
+            .await?
+            .into_iter()
+            .map(|transition| (transition.from, transition.to))
+            .collect();
+        assert_eq!(
+            transitions,
+            vec![
+                (None, State::Joined),
+                (Some(State::Joined), State::Welcomed),
+            ],
+        );
+
+        Ok(())
+    }
+
+    fn fields(about: &str) -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("name".to_owned(), "Sam".to_owned()),
+            ("about".to_owned(), about.to_owned()),
+        ])
+    }
+
+    #[sqlx::test]
+    async fn intro_find_similar_finds_near_duplicates(db: PgPool) -> Result<()> {
+        let other_user_id = UserId::new(3);
+        let archived_user_id = UserId::new(4);
+        let about = "I live in Berlin, work as a nurse, and spend my weekends climbing.";
+
+        intro::set(&db, GUILD_ID, USER_ID, &fields(about), Utc::now()).await?;
+        intro::set(&db, GUILD_ID, other_user_id, &fields(about), Utc::now()).await?;
+        intro::set(&db, GUILD_ID, archived_user_id, &fields(about), Utc::now()).await?;
+        intro::archive(&db, GUILD_ID, archived_user_id, Utc::now()).await?;
+
+        let mut conn = db.acquire().await?;
+
+        // The member's own intro and archived intros aren't compared.
+        let text = format!("{about}!\nSam");
+        assert_eq!(
+            intro::find_similar(&mut conn, GUILD_ID, USER_ID, &text, 0.8).await?,
+            Some(other_user_id),
+        );
+        assert_eq!(
+            intro::find_similar(&mut conn, GUILD_ID, other_user_id, &text, 0.8).await?,
+            Some(USER_ID),
+        );
+
+        let text = "Hey! I'm into board games, baking bread, and long walks by the sea.";
+        assert_eq!(
+            intro::find_similar(&mut conn, GUILD_ID, USER_ID, text, 0.8).await?,
+            None,
+        );
+
+        Ok(())
     }
 }
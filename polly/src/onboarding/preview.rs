@@ -0,0 +1,137 @@
+use std::fmt::Write;
+
+use anyhow::Context as _;
+use serenity::all::{Member, Mentionable};
+
+use super::{
+    get_introduced,
+    get_members,
+    lockdown,
+    needs_kick,
+    needs_quarantine,
+    persist,
+    reply_long,
+};
+use crate::{PoiseApplicationContext, context::Context, error::Result, l10n::tr};
+
+fn write_member(
+    ctx: &impl Context,
+    locales: &[&str],
+    w: &mut impl Write,
+    action: String,
+    member: &Member,
+    introduced: bool,
+) -> Result<()> {
+    let joined = member.joined_at.map_or_else(
+        || tr!(ctx, locales, "preview-unknown"),
+        |joined_at| format!("<t:{}:R>", joined_at.unix_timestamp()),
+    );
+
+    let roles = if member.roles.is_empty() {
+        tr!(ctx, locales, "preview-none")
+    } else {
+        member
+            .roles
+            .iter()
+            .map(|role_id| role_id.mention().to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let introduced = if introduced {
+        tr!(ctx, locales, "preview-yes")
+    } else {
+        tr!(ctx, locales, "preview-no")
+    };
+
+    writeln!(
+        w,
+        "{}",
+        tr!(
+            ctx,
+            locales,
+            "preview-member",
+            action = action,
+            member = member.mention().to_string(),
+            tag = member.user.tag(),
+            joined = joined,
+            roles = roles,
+            introduced = introduced,
+        ),
+    )?;
+
+    Ok(())
+}
+
+/// Show who would be quarantined or kicked, without doing it
+#[poise::command(guild_only, slash_command)]
+#[tracing::instrument(
+    fields(
+        ctx.id = ctx.id(),
+        ctx.guild_id = %ctx.guild_id().unwrap_or_default(),
+        ctx.author.id = %ctx.author().id,
+    ),
+    skip(ctx),
+)]
+pub async fn preview(ctx: PoiseApplicationContext<'_>) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx.guild_id().context("Context has no guild_id")?;
+    let config = ctx.config().guild(guild_id)?;
+    let locales = config.user_locales(&ctx.interaction.locale);
+    let now = chrono::Utc::now();
+
+    let members = get_members(&ctx, guild_id).await?;
+    let introduced = get_introduced(&ctx, guild_id).await?;
+    let states = persist::state::get_all(ctx.db(), guild_id).await?;
+    let intros_paused = lockdown::pauses_intros(&ctx, config, guild_id).await?;
+    let intros_resumed_at = lockdown::intros_resumed_at(&ctx, config, guild_id).await?;
+
+    let mut n_quarantine = 0;
+    let mut n_kick = 0;
+    let mut lines = String::new();
+
+    for member in &members {
+        let state = states.get(&member.user.id).copied();
+        let is_introduced = introduced.contains(&member.user.id);
+
+        let action = if !intros_paused
+            && needs_kick(
+                &ctx,
+                config,
+                member,
+                state.map(|(state, _)| state),
+                intros_resumed_at,
+                now,
+            )
+            .await?
+        {
+            n_kick += 1;
+            tr!(&ctx, locales, "preview-kick")
+        } else if needs_quarantine(config, member, state, is_introduced, now) {
+            n_quarantine += 1;
+            tr!(&ctx, locales, "preview-quarantine")
+        } else {
+            continue;
+        };
+
+        write_member(&ctx, &locales, &mut lines, action, member, is_introduced)?;
+    }
+
+    let mut text = String::new();
+    writeln!(
+        text,
+        "{}",
+        tr!(
+            &ctx,
+            locales,
+            "preview-summary",
+            members = members.len(),
+            quarantine = n_quarantine,
+            kick = n_kick,
+        ),
+    )?;
+    text.push_str(&lines);
+
+    reply_long(ctx, text, "onboarding_preview.md").await
+}
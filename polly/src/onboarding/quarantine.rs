@@ -1,31 +1,130 @@
 use anyhow::Context as _;
-use serenity::all::{CreateActionRow, CreateMessage, GuildId, Member, Message, UserId};
+use chrono::{DateTime, Utc};
+use serenity::all::{
+    ChannelId,
+    ChannelType,
+    CreateActionRow,
+    CreateMessage,
+    CreateThread,
+    EditMessage,
+    EditThread,
+    GuildId,
+    Member,
+    Message,
+    MessageId,
+    UserId,
+};
+use sqlx::PgConnection;
 use tracing::info;
 
-use super::{intro, persist};
+use super::{
+    age,
+    intro,
+    new_accounts,
+    persist::{self, reminder::Reminder, state::State},
+    reminders::delete_reminder_messages,
+    rules,
+    state,
+};
 use crate::{
+    config::GuildConfig,
     context::Context,
-    error::{Result, is_http_not_found},
+    error::{Result, bail, is_http_not_found},
+    l10n::tr,
 };
 
-fn create_welcome_message(guild_name: &str, member: &Member) -> CreateMessage {
-    let content = format!(
-        "Welcome to {guild_name}, {member}! Please introduce yourself before you can start chatting.\n\
-        \n\
-        **Rules**\n\
-        1. **DM = BAN**. This server is not for dating or hookups.\n\
-        2. You must be at least 18 years old.\n\
-        3. Always follow the Code of Conduct, available at https://polyam.eu/coc.html.\n\
-        4. Speak English in the common channels."
-    );
+// Discord's limit on channel names.
+const THREAD_NAME_LIMIT: usize = 100;
+
+fn create_welcome_message(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_name: &str,
+    member: &Member,
+    new_account: bool,
+    can_restore: bool,
+) -> CreateMessage {
+    let content = new_accounts::welcome_template(config, new_account).render(&[
+        ("guild_name", &guild_name),
+        ("member", member),
+        ("coc_url", &config.coc_url),
+        ("rules", &rules::render(config)),
+    ]);
 
     CreateMessage::new()
         .content(content)
-        .components(vec![CreateActionRow::Buttons(vec![intro::create_button()])])
+        .components(create_welcome_components(
+            ctx,
+            config,
+            member.guild_id,
+            false,
+            can_restore,
+        ))
+}
+
+fn create_welcome_components(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_id: GuildId,
+    awaiting_review: bool,
+    can_restore: bool,
+) -> Vec<CreateActionRow> {
+    let button = if awaiting_review {
+        intro::create_button(ctx, config)
+            .label(tr!(ctx, config.guild_locales(), "welcome-awaiting-review"))
+            .disabled(true)
+    } else {
+        intro::create_button(ctx, config)
+    };
+
+    let locales = config.guild_locales();
+
+    let mut buttons = vec![
+        age::create_button(ctx, &locales),
+        rules::create_button(ctx, config, guild_id, &locales),
+        button,
+    ];
+    if can_restore && !awaiting_review {
+        buttons.push(intro::create_restore_button(ctx, config));
+    }
+
+    vec![CreateActionRow::Buttons(buttons)]
 }
 
+/// Sends the welcome message again, in `thread` if the member already has a welcome thread.
 #[tracing::instrument(skip_all)]
-pub async fn send_welcome_message(ctx: &impl Context, member: &Member) -> Result<Message> {
+pub async fn send_welcome_message(
+    ctx: &impl Context,
+    member: &Member,
+    thread: Option<ChannelId>,
+) -> Result<Message> {
+    let config = ctx.config().guild(member.guild_id)?;
+
+    let new_account = new_accounts::was_new(ctx, config, member.guild_id, &member.user).await?;
+    let message = post_welcome_message(ctx, member, new_account, thread).await?;
+
+    persist::welcome_message::set(
+        ctx.db(),
+        member.guild_id,
+        member.user.id,
+        message.channel_id,
+        message.id,
+        config.welcome_threads,
+        chrono::Utc::now(),
+    )
+    .await?;
+
+    Ok(message)
+}
+
+/// Sends the welcome message, without recording it.
+/// It's sent in `thread` if given, or else in a new thread if they're enabled.
+async fn post_welcome_message(
+    ctx: &impl Context,
+    member: &Member,
+    new_account: bool,
+    thread: Option<ChannelId>,
+) -> Result<Message> {
     let config = ctx.config().guild(member.guild_id)?;
 
     let channel = config
@@ -38,53 +137,221 @@ pub async fn send_welcome_message(ctx: &impl Context, member: &Member) -> Result
     assert!(channel.guild_id == member.guild_id);
 
     let guild = member.guild_id.to_partial_guild(ctx.serenity()).await?;
+    let can_restore = intro::get_archived(ctx, member.guild_id, member.user.id)
+        .await?
+        .is_some();
 
-    let message = channel
-        .send_message(ctx.serenity(), create_welcome_message(&guild.name, member))
-        .await?;
+    let welcome_channel = if let Some(thread) = thread {
+        thread
+    } else if config.welcome_threads {
+        let name: String = tr!(
+            ctx,
+            config.guild_locales(),
+            "welcome-thread-name",
+            user = member.user.name.as_str(),
+        )
+        .chars()
+        .take(THREAD_NAME_LIMIT)
+        .collect();
 
-    persist::welcome_message::set(
-        ctx.db(),
-        member.guild_id,
-        member.user.id,
-        channel.id,
-        message.id,
-    )
-    .await?;
+        // The welcome message mentions the member, which adds them to the thread.
+        let thread = channel
+            .create_thread(
+                ctx.serenity(),
+                CreateThread::new(name)
+                    .kind(ChannelType::PrivateThread)
+                    .invitable(false),
+            )
+            .await?;
+
+        thread.id
+    } else {
+        channel.id
+    };
+
+    let message = welcome_channel
+        .send_message(
+            ctx.serenity(),
+            create_welcome_message(ctx, config, &guild.name, member, new_account, can_restore),
+        )
+        .await?;
 
     Ok(message)
 }
 
-pub async fn delete_welcome_message(
+/// Disables the intro buttons while the member's intro is awaiting review.
+pub async fn set_welcome_awaiting_review(
     ctx: &impl Context,
     guild_id: GuildId,
     user_id: UserId,
+    awaiting_review: bool,
 ) -> Result<()> {
-    let mut tx = ctx.db().begin().await?;
+    let config = ctx.config().guild(guild_id)?;
 
     if let Some((channel_id, message_id)) =
-        persist::welcome_message::get(&mut *tx, guild_id, user_id).await?
+        persist::welcome_message::get(ctx.db(), guild_id, user_id).await?
     {
+        let can_restore = intro::get_archived(ctx, guild_id, user_id).await?.is_some();
+
         channel_id
-            .delete_message(ctx.serenity(), message_id)
-            .await
-            .or_else(|err| {
-                // If the message was already deleted, continue with deleting the database row.
-                if is_http_not_found(&err) {
-                    Ok(())
-                } else {
-                    Err(err)
-                }
-            })?;
-
-        persist::welcome_message::delete(&mut *tx, guild_id, user_id).await?;
+            .edit_message(
+                ctx.serenity(),
+                message_id,
+                EditMessage::new().components(create_welcome_components(
+                    ctx,
+                    config,
+                    guild_id,
+                    awaiting_review,
+                    can_restore,
+                )),
+            )
+            .await?;
     }
 
+    Ok(())
+}
+
+pub async fn delete_welcome_message(
+    ctx: &impl Context,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<()> {
+    let mut tx = ctx.db().begin().await?;
+
+    // The row stays locked until the transaction ends, so the delete event finds nothing.
+    let welcome_message = persist::welcome_message::take(&mut *tx, guild_id, user_id).await?;
+    delete_forgotten(
+        ctx,
+        &Forgotten {
+            welcome_message,
+            reminders: Vec::new(),
+        },
+    )
+    .await?;
+
     tx.commit().await?;
 
     Ok(())
 }
 
+/// Onboarding messages that were deleted from the database, and are left to delete from Discord.
+#[derive(Default)]
+struct Forgotten {
+    /// The channel, message, and whether the channel is a thread created for the member.
+    welcome_message: Option<(ChannelId, MessageId, bool)>,
+    reminders: Vec<Reminder>,
+}
+
+/// Deletes the member's welcome message and reminders from the database.
+async fn forget_messages(
+    conn: &mut PgConnection,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<Forgotten> {
+    Ok(Forgotten {
+        welcome_message: persist::welcome_message::take(&mut *conn, guild_id, user_id).await?,
+        reminders: persist::reminder::take_all(&mut *conn, guild_id, user_id).await?,
+    })
+}
+
+async fn delete_forgotten(ctx: &impl Context, forgotten: &Forgotten) -> Result<()> {
+    // Reminders can't be deleted from a locked thread.
+    delete_reminder_messages(ctx, &forgotten.reminders).await?;
+
+    let Some((channel_id, message_id, thread)) = forgotten.welcome_message else {
+        return Ok(());
+    };
+
+    let result = if thread {
+        // Keep the thread, so that moderators can still read the conversation.
+        channel_id
+            .edit_thread(
+                ctx.serenity(),
+                EditThread::new().archived(true).locked(true),
+            )
+            .await
+            .map(|_| ())
+    } else {
+        channel_id.delete_message(ctx.serenity(), message_id).await
+    };
+
+    result.or_else(|err| {
+        // If the message was already deleted, the database row can go anyway.
+        if is_http_not_found(&err) {
+            Ok(())
+        } else {
+            Err(err)
+        }
+    })?;
+
+    Ok(())
+}
+
+/// The database part of `quarantine`, once the new welcome message was sent.
+/// Returns the old messages, to delete from Discord.
+async fn record_quarantine(
+    conn: &mut PgConnection,
+    guild_id: GuildId,
+    user_id: UserId,
+    (channel_id, message_id, thread): (ChannelId, MessageId, bool),
+    new_account: bool,
+    at: DateTime<Utc>,
+) -> Result<Forgotten> {
+    let forgotten = forget_messages(&mut *conn, guild_id, user_id).await?;
+
+    persist::welcome_message::set(
+        &mut *conn, guild_id, user_id, channel_id, message_id, thread, at,
+    )
+    .await?;
+
+    state::record(&mut *conn, guild_id, user_id, State::Welcomed, at).await?;
+    persist::state::set_new_account(&mut *conn, guild_id, user_id, new_account).await?;
+
+    Ok(forgotten)
+}
+
+/// The database part of `unquarantine`, which can be repeated.
+/// Returns the messages to delete from Discord.
+async fn record_unquarantine(
+    conn: &mut PgConnection,
+    guild_id: GuildId,
+    user_id: UserId,
+    at: DateTime<Utc>,
+) -> Result<Forgotten> {
+    let forgotten = forget_messages(&mut *conn, guild_id, user_id).await?;
+
+    state::record(&mut *conn, guild_id, user_id, State::Member, at).await?;
+
+    Ok(forgotten)
+}
+
+/// When the member's onboarding deadline started counting: when they joined,
+/// or when they were welcomed, if they were quarantined later.
+pub async fn quarantined_since(
+    ctx: &impl Context,
+    member: &Member,
+) -> Result<Option<DateTime<Utc>>> {
+    let joined_at = member.joined_at.map(|joined_at| *joined_at);
+    let sent_at =
+        persist::welcome_message::get_sent_at(ctx.db(), member.guild_id, member.user.id).await?;
+
+    Ok(joined_at.max(sent_at))
+}
+
+/// When the member's onboarding deadline and reminders start counting: when they were
+/// quarantined, or when intros resumed after a lockdown that paused them, if that was later.
+pub async fn deadline_start(
+    ctx: &impl Context,
+    member: &Member,
+    intros_resumed_at: Option<DateTime<Utc>>,
+) -> Result<Option<DateTime<Utc>>> {
+    let since = quarantined_since(ctx, member).await?;
+
+    Ok(since.map(|since| since.max(intros_resumed_at.unwrap_or(since))))
+}
+
+/// Quarantines the member and welcomes them. If they're already quarantined,
+/// their welcome message is replaced.
 #[tracing::instrument(skip_all)]
 pub async fn quarantine(ctx: &impl Context, member: &Member) -> Result<()> {
     let config = ctx.config().guild(member.guild_id)?;
@@ -93,7 +360,27 @@ pub async fn quarantine(ctx: &impl Context, member: &Member) -> Result<()> {
         .add_role(ctx.serenity(), config.quarantine_role)
         .await?;
 
-    send_welcome_message(ctx, member).await?;
+    // Saved, so that the intro is still held if the account gets old enough before it's submitted.
+    let new_account = new_accounts::is_new(config, &member.user);
+    let welcome_message = post_welcome_message(ctx, member, new_account, None).await?;
+
+    let mut tx = ctx.db().begin().await?;
+    let forgotten = record_quarantine(
+        &mut tx,
+        member.guild_id,
+        member.user.id,
+        (
+            welcome_message.channel_id,
+            welcome_message.id,
+            config.welcome_threads,
+        ),
+        new_account,
+        chrono::Utc::now(),
+    )
+    .await?;
+    // The rows stay locked until the transaction ends, so the delete events find nothing.
+    delete_forgotten(ctx, &forgotten).await?;
+    tx.commit().await?;
 
     info!(
         %member.guild_id,
@@ -105,22 +392,179 @@ pub async fn quarantine(ctx: &impl Context, member: &Member) -> Result<()> {
     Ok(())
 }
 
+/// Releases the member from quarantine, and cleans up after their onboarding.
+/// The clean up is also done if they already were released, in case it failed then.
 #[tracing::instrument(skip_all)]
 pub async fn unquarantine(ctx: &impl Context, member: &mut Member) -> Result<()> {
     let config = ctx.config().guild(member.guild_id)?;
 
-    member
-        .remove_role(ctx.serenity(), config.quarantine_role)
-        .await?;
+    if member.roles.contains(&config.quarantine_role) {
+        if !age::is_confirmed(ctx, member.guild_id, member.user.id).await? {
+            bail!("Member has not confirmed their age");
+        }
+        if !rules::is_accepted(ctx, config, member.guild_id, member.user.id).await? {
+            bail!("Member has not accepted the rules");
+        }
 
-    delete_welcome_message(ctx, member.guild_id, member.user.id).await?;
+        member
+            .remove_role(ctx.serenity(), config.quarantine_role)
+            .await?;
 
-    info!(
-        %member.guild_id,
-        %member.user.id,
-        member.user.tag = member.user.tag(),
-        "Unquarantined member"
-    );
+        info!(
+            %member.guild_id,
+            %member.user.id,
+            member.user.tag = member.user.tag(),
+            "Unquarantined member"
+        );
+    }
+
+    let mut tx = ctx.db().begin().await?;
+    let forgotten =
+        record_unquarantine(&mut tx, member.guild_id, member.user.id, chrono::Utc::now()).await?;
+    delete_forgotten(ctx, &forgotten).await?;
+    tx.commit().await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::Utc;
+    use serenity::all::{ChannelId, GuildId, MessageId, UserId};
+    use sqlx::PgPool;
+
+    use super::{Forgotten, record_quarantine, record_unquarantine};
+    use crate::{
+        error::Result,
+        onboarding::persist::{self, state::State},
+    };
+
+    const GUILD_ID: GuildId = GuildId::new(1);
+    const USER_ID: UserId = UserId::new(2);
+    const CHANNEL_ID: ChannelId = ChannelId::new(10);
+
+    fn forgotten_message_ids(forgotten: &Forgotten) -> (Option<MessageId>, Vec<MessageId>) {
+        (
+            forgotten
+                .welcome_message
+                .map(|(_, message_id, _)| message_id),
+            forgotten
+                .reminders
+                .iter()
+                .map(|reminder| reminder.message_id)
+                .collect(),
+        )
+    }
+
+    async fn transitions(db: &PgPool) -> Result<Vec<(Option<State>, State)>> {
+        Ok(persist::state::get_transitions(db, GUILD_ID, USER_ID)
+            .await?
+            .into_iter()
+            .map(|transition| (transition.from, transition.to))
+            .collect())
+    }
+
+    #[sqlx::test]
+    async fn requarantine_replaces_welcome_message(db: PgPool) -> Result<()> {
+        let mut conn = db.acquire().await?;
+
+        let forgotten = record_quarantine(
+            &mut conn,
+            GUILD_ID,
+            USER_ID,
+            (CHANNEL_ID, MessageId::new(11), false),
+            true,
+            Utc::now(),
+        )
+        .await?;
+        assert_eq!(forgotten_message_ids(&forgotten), (None, vec![]));
+        assert_eq!(
+            persist::state::get_new_account(&db, GUILD_ID, USER_ID).await?,
+            Some(true)
+        );
+
+        persist::reminder::add(
+            &mut *conn,
+            GUILD_ID,
+            USER_ID,
+            Duration::from_secs(60),
+            CHANNEL_ID,
+            MessageId::new(12),
+            Utc::now(),
+        )
+        .await?;
+
+        // The old welcome message and reminder are returned, to be deleted from Discord.
+        let forgotten = record_quarantine(
+            &mut conn,
+            GUILD_ID,
+            USER_ID,
+            (CHANNEL_ID, MessageId::new(13), false),
+            false,
+            Utc::now(),
+        )
+        .await?;
+        assert_eq!(
+            forgotten_message_ids(&forgotten),
+            (Some(MessageId::new(11)), vec![MessageId::new(12)]),
+        );
+
+        assert_eq!(
+            persist::welcome_message::get(&db, GUILD_ID, USER_ID).await?,
+            Some((CHANNEL_ID, MessageId::new(13))),
+        );
+        assert!(
+            persist::reminder::get_all(&db, GUILD_ID, USER_ID)
+                .await?
+                .is_empty()
+        );
+        // Whether the account is new is decided again with the new welcome message.
+        assert_eq!(
+            persist::state::get_new_account(&db, GUILD_ID, USER_ID).await?,
+            Some(false)
+        );
+        assert_eq!(transitions(&db).await?, vec![(None, State::Welcomed)]);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn unquarantine_twice_is_a_no_op(db: PgPool) -> Result<()> {
+        let mut conn = db.acquire().await?;
+
+        record_quarantine(
+            &mut conn,
+            GUILD_ID,
+            USER_ID,
+            (CHANNEL_ID, MessageId::new(11), true),
+            false,
+            Utc::now(),
+        )
+        .await?;
+
+        let forgotten = record_unquarantine(&mut conn, GUILD_ID, USER_ID, Utc::now()).await?;
+        assert_eq!(
+            forgotten_message_ids(&forgotten),
+            (Some(MessageId::new(11)), vec![]),
+        );
+
+        let forgotten = record_unquarantine(&mut conn, GUILD_ID, USER_ID, Utc::now()).await?;
+        assert_eq!(forgotten_message_ids(&forgotten), (None, vec![]));
+
+        assert_eq!(
+            persist::welcome_message::get(&db, GUILD_ID, USER_ID).await?,
+            None
+        );
+        assert_eq!(
+            transitions(&db).await?,
+            vec![
+                (None, State::Welcomed),
+                (Some(State::Welcomed), State::Member)
+            ],
+        );
+
+        Ok(())
+    }
+}
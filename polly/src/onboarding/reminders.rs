@@ -0,0 +1,196 @@
+use anyhow::Context as _;
+use chrono::{DateTime, Utc};
+use serenity::all::{CreateActionRow, CreateMessage, GuildId, Member, UserId};
+use tracing::info;
+
+use super::{
+    connected_configured_guilds,
+    get_members,
+    intro,
+    lockdown,
+    persist::{self, reminder::Reminder, state::State},
+    quarantine::deadline_start,
+};
+use crate::{
+    config::GuildConfig,
+    context::Context,
+    error::{Result, is_http_not_found},
+    l10n::tr,
+};
+
+#[tracing::instrument(
+    fields(
+        %member.guild_id,
+        %member.user.id,
+        member.user.tag = member.user.tag(),
+    ),
+    skip_all,
+)]
+async fn remind_member(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_name: &str,
+    member: &Member,
+    intros_resumed_at: Option<DateTime<Utc>>,
+) -> Result<()> {
+    let now = Utc::now();
+
+    let Some(start) = deadline_start(ctx, member, intros_resumed_at).await? else {
+        return Ok(());
+    };
+    let since_start = (now - start).to_std().unwrap_or_default();
+
+    if since_start >= config.onboarding_deadline {
+        return Ok(());
+    }
+
+    let mut sent = persist::reminder::get_all(ctx.db(), member.guild_id, member.user.id).await?;
+
+    // Reminders from before intros were paused by a lockdown give the wrong deadline, and the
+    // member is reminded again from the start.
+    if sent.iter().any(|reminder| reminder.sent_at < start) {
+        delete_reminders(ctx, member.guild_id, member.user.id).await?;
+        sent.clear();
+    }
+
+    // If several reminders are due, for example after downtime, only send the latest one.
+    let Some(&offset) = config
+        .onboarding_reminders
+        .iter()
+        .rev()
+        .find(|&&offset| offset <= since_start)
+    else {
+        return Ok(());
+    };
+
+    if sent.iter().any(|reminder| reminder.offset >= offset) {
+        return Ok(());
+    }
+
+    let deadline = format!("<t:{}:R>", (start + config.onboarding_deadline).timestamp());
+
+    let channel_id =
+        persist::welcome_message::get_thread(ctx.db(), member.guild_id, member.user.id)
+            .await?
+            .unwrap_or(config.quarantine_channel);
+
+    let message = channel_id
+        .send_message(
+            ctx.serenity(),
+            CreateMessage::new()
+                .content(tr!(
+                    ctx,
+                    config.guild_locales(),
+                    "reminder-ping",
+                    member = member.to_string(),
+                    deadline = deadline.clone(),
+                ))
+                .components(vec![CreateActionRow::Buttons(vec![intro::create_button(
+                    ctx, config,
+                )])]),
+        )
+        .await?;
+
+    persist::reminder::add(
+        ctx.db(),
+        member.guild_id,
+        member.user.id,
+        offset,
+        message.channel_id,
+        message.id,
+        now,
+    )
+    .await?;
+
+    let dm_result = async {
+        let dm_channel = member.user.create_dm_channel(ctx.serenity()).await?;
+        dm_channel
+            .say(
+                ctx.serenity(),
+                tr!(
+                    ctx,
+                    config.guild_locales(),
+                    "reminder-dm",
+                    guild = guild_name,
+                    deadline = deadline,
+                    link = message.link(),
+                ),
+            )
+            .await
+    }
+    .await;
+
+    // Members can disable DMs, but they still see the ping.
+    if let Err(err) = dm_result {
+        tracing::warn!(error = ?err, "Could not DM member");
+    }
+
+    info!(?offset, "Reminded member");
+
+    Ok(())
+}
+
+pub async fn send_reminders(ctx: &impl Context) -> Result<()> {
+    for (guild_id, config) in connected_configured_guilds(ctx) {
+        if config.onboarding_reminders.is_empty() {
+            continue;
+        }
+
+        // Members can't introduce themselves during the lockdown.
+        if lockdown::pauses_intros(ctx, config, guild_id).await? {
+            continue;
+        }
+
+        let guild_name = guild_id
+            .name(ctx.serenity())
+            .context("Guild not available in cache")?;
+        let guild_name = guild_name.as_str();
+
+        let states = persist::state::get_all(ctx.db(), guild_id).await?;
+        let intros_resumed_at = lockdown::intros_resumed_at(ctx, config, guild_id).await?;
+
+        // Members whose intro is under review are waiting on a moderator, not the other way around.
+        for member in get_members(ctx, guild_id).await? {
+            if states.get(&member.user.id).map(|&(state, _)| state) == Some(State::Welcomed) {
+                remind_member(ctx, config, guild_name, &member, intros_resumed_at).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Deletes the reminder pings, once they're no longer relevant.
+pub async fn delete_reminders(
+    ctx: &impl Context,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<()> {
+    let mut tx = ctx.db().begin().await?;
+
+    let reminders = persist::reminder::take_all(&mut *tx, guild_id, user_id).await?;
+    delete_reminder_messages(ctx, &reminders).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Deletes the pings of reminders that were already deleted from the database.
+pub async fn delete_reminder_messages(ctx: &impl Context, reminders: &[Reminder]) -> Result<()> {
+    for reminder in reminders {
+        reminder
+            .channel_id
+            .delete_message(ctx.serenity(), reminder.message_id)
+            .await
+            .or_else(|err| {
+                if is_http_not_found(&err) {
+                    Ok(())
+                } else {
+                    Err(err)
+                }
+            })?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,658 @@
+use anyhow::Context as _;
+use serenity::all::{
+    ActionRowComponent,
+    ButtonStyle,
+    ChannelId,
+    ComponentInteraction,
+    CreateActionRow,
+    CreateButton,
+    CreateInputText,
+    CreateInteractionResponse,
+    CreateInteractionResponseFollowup,
+    CreateInteractionResponseMessage,
+    CreateMessage,
+    CreateModal,
+    EditInteractionResponse,
+    EditMessage,
+    GuildId,
+    InputTextStyle,
+    Member,
+    Mentionable,
+    MessageId,
+    ModalInteraction,
+    Permissions,
+    User,
+    UserId,
+};
+use tracing::{info, warn};
+
+use super::{
+    age,
+    intro::{self, Intro},
+    persist::{self, state::State},
+    quarantine::{set_welcome_awaiting_review, unquarantine},
+    rules,
+    state,
+};
+use crate::{
+    config::GuildConfig,
+    context::Context,
+    error::{Result, is_http_not_found},
+    l10n::tr,
+};
+
+const APPROVE_ID: &str = "onboarding_review_approve";
+const REJECT_ID: &str = "onboarding_review_reject";
+const REQUEST_CHANGES_ID: &str = "onboarding_review_request_changes";
+const REASON_ID: &str = "reason";
+
+#[derive(Clone, Copy, Debug)]
+pub enum Action {
+    Approve,
+    Reject,
+    RequestChanges,
+}
+
+impl Action {
+    /// Parses the custom ID of a review button or modal, which includes the ID of the reviewed member.
+    pub fn parse_custom_id(custom_id: &str) -> Option<(Self, UserId)> {
+        let (id, user_id) = custom_id.split_once(':')?;
+
+        let action = match id {
+            APPROVE_ID => Action::Approve,
+            REJECT_ID => Action::Reject,
+            REQUEST_CHANGES_ID => Action::RequestChanges,
+            _ => return None,
+        };
+
+        let user_id = user_id.parse::<u64>().ok().filter(|&id| id != 0)?;
+
+        Some((action, UserId::new(user_id)))
+    }
+
+    fn custom_id(self, user_id: UserId) -> String {
+        let id = match self {
+            Action::Approve => APPROVE_ID,
+            Action::Reject => REJECT_ID,
+            Action::RequestChanges => REQUEST_CHANGES_ID,
+        };
+
+        format!("{id}:{user_id}")
+    }
+
+    /// The decline that the action asks a reason for, unless it approves.
+    fn decline(self) -> Option<Decline> {
+        match self {
+            Action::Approve => None,
+            Action::Reject => Some(Decline::Reject),
+            Action::RequestChanges => Some(Decline::RequestChanges),
+        }
+    }
+}
+
+/// An action that sends the member a reason, given in a modal.
+#[derive(Clone, Copy, Debug)]
+pub enum Decline {
+    Reject,
+    RequestChanges,
+}
+
+impl Decline {
+    /// Parses the custom ID of a reason modal, which includes the ID of the reviewed member.
+    pub fn parse_custom_id(custom_id: &str) -> Option<(Self, UserId)> {
+        let (action, user_id) = Action::parse_custom_id(custom_id)?;
+
+        Some((action.decline()?, user_id))
+    }
+
+    fn custom_id(self, user_id: UserId) -> String {
+        let action = match self {
+            Decline::Reject => Action::Reject,
+            Decline::RequestChanges => Action::RequestChanges,
+        };
+
+        action.custom_id(user_id)
+    }
+}
+
+fn create_components(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    user_id: UserId,
+) -> Vec<CreateActionRow> {
+    let locales = config.guild_locales();
+
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(Action::Approve.custom_id(user_id))
+            .label(tr!(ctx, locales, "review-approve"))
+            .style(ButtonStyle::Success),
+        CreateButton::new(Action::Reject.custom_id(user_id))
+            .label(tr!(ctx, locales, "review-reject"))
+            .style(ButtonStyle::Danger),
+        CreateButton::new(Action::RequestChanges.custom_id(user_id))
+            .label(tr!(ctx, locales, "review-request-changes"))
+            .style(ButtonStyle::Secondary),
+    ])]
+}
+
+fn create_reason_modal(
+    ctx: &impl Context,
+    locales: &[&str],
+    decline: Decline,
+    user_id: UserId,
+) -> CreateModal {
+    let title = match decline {
+        Decline::Reject => tr!(ctx, locales, "review-reject-title"),
+        Decline::RequestChanges => tr!(ctx, locales, "review-request-changes-title"),
+    };
+
+    let reason = CreateInputText::new(
+        InputTextStyle::Paragraph,
+        tr!(ctx, locales, "review-reason"),
+        REASON_ID,
+    )
+    .placeholder(tr!(ctx, locales, "review-reason-placeholder"))
+    .required(true)
+    .max_length(1000);
+
+    CreateModal::new(decline.custom_id(user_id), title)
+        .components(vec![CreateActionRow::InputText(reason)])
+}
+
+fn is_moderator(member: Option<&Member>) -> bool {
+    member
+        .and_then(|member| member.permissions)
+        .is_some_and(Permissions::moderate_members)
+}
+
+pub async fn is_pending(ctx: &impl Context, guild_id: GuildId, user_id: UserId) -> Result<bool> {
+    let pending = persist::intro_review::get(ctx.db(), guild_id, user_id)
+        .await?
+        .is_some_and(|review| !review.changes_requested);
+
+    Ok(pending)
+}
+
+/// Sends the intro to the review channel instead of publishing it.
+#[tracing::instrument(skip_all)]
+pub async fn submit(
+    ctx: &impl Context,
+    member: &Member,
+    intro: &Intro,
+    review_channel: ChannelId,
+) -> Result<()> {
+    let config = ctx.config().guild(member.guild_id)?;
+
+    let mut tx = ctx.db().begin().await?;
+
+    // Only the latest submission can be reviewed.
+    if let Some(previous) =
+        persist::intro_review::get(&mut *tx, member.guild_id, member.user.id).await?
+        && !previous.changes_requested
+    {
+        previous
+            .channel_id
+            .delete_message(ctx.serenity(), previous.message_id)
+            .await
+            .or_else(|err| {
+                if is_http_not_found(&err) {
+                    Ok(())
+                } else {
+                    Err(err)
+                }
+            })?;
+    }
+
+    let message = review_channel
+        .send_message(
+            ctx.serenity(),
+            CreateMessage::new()
+                .content(tr!(
+                    ctx,
+                    config.guild_locales(),
+                    "review-message",
+                    member = member.to_string(),
+                ))
+                .embed(intro::create_embed(
+                    config,
+                    &member.user,
+                    member.display_name(),
+                    intro,
+                ))
+                .components(create_components(ctx, config, member.user.id)),
+        )
+        .await?;
+
+    persist::intro_review::set(
+        &mut *tx,
+        member.guild_id,
+        member.user.id,
+        &intro.fields,
+        review_channel,
+        message.id,
+        chrono::Utc::now(),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    set_welcome_awaiting_review(ctx, member.guild_id, member.user.id, true).await?;
+
+    info!(
+        %member.guild_id,
+        %member.user.id,
+        member.user.tag = member.user.tag(),
+        "Submitted intro for review"
+    );
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(ctx, interaction))]
+pub async fn component_interaction(
+    ctx: &impl Context,
+    interaction: &ComponentInteraction,
+    action: Action,
+    user_id: UserId,
+) -> Result<()> {
+    let guild_id = interaction
+        .guild_id
+        .context("Interaction has no guild_id")?;
+    let config = ctx.config().guild(guild_id)?;
+    let locales = config.user_locales(&interaction.locale);
+
+    let pending = persist::intro_review::get(ctx.db(), guild_id, user_id)
+        .await?
+        .filter(|review| !review.changes_requested && review.message_id == interaction.message.id);
+
+    let approving = action.decline().is_none();
+    let member = user_id.mention().to_string();
+
+    // Approving releases the member, which they must have confirmed their age and accepted the
+    // current rules for.
+    let refusal = if !is_moderator(interaction.member.as_ref()) {
+        Some(tr!(ctx, locales, "review-not-moderator"))
+    } else if pending.is_none() {
+        Some(tr!(ctx, locales, "review-not-pending"))
+    } else if approving && !age::is_confirmed(ctx, guild_id, user_id).await? {
+        Some(tr!(
+            ctx,
+            locales,
+            "review-age-not-confirmed",
+            member = member.clone()
+        ))
+    } else if approving && !rules::is_accepted(ctx, config, guild_id, user_id).await? {
+        Some(tr!(
+            ctx,
+            locales,
+            "review-rules-not-accepted",
+            member = member
+        ))
+    } else {
+        None
+    };
+
+    if let Some(refusal) = refusal {
+        interaction
+            .create_response(
+                ctx.serenity(),
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content(refusal)
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    match action.decline() {
+        None => approve(ctx, interaction, config, guild_id, user_id).await?,
+
+        Some(decline) => {
+            interaction
+                .create_response(
+                    ctx.serenity(),
+                    CreateInteractionResponse::Modal(create_reason_modal(
+                        ctx, &locales, decline, user_id,
+                    )),
+                )
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn approve(
+    ctx: &impl Context,
+    interaction: &ComponentInteraction,
+    config: &GuildConfig,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<()> {
+    let locales = config.user_locales(&interaction.locale);
+
+    interaction
+        .create_response(ctx.serenity(), CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    let approved = approve_intro(
+        ctx,
+        guild_id,
+        user_id,
+        interaction.message.id,
+        &interaction.user,
+    )
+    .await;
+
+    if let Err(err) = approved {
+        interaction
+            .create_followup(
+                ctx.serenity(),
+                CreateInteractionResponseFollowup::new()
+                    .content(tr!(
+                        ctx,
+                        locales,
+                        "review-approval-failed",
+                        member = user_id.mention().to_string(),
+                        error = err.to_string(),
+                    ))
+                    .ephemeral(true),
+            )
+            .await?;
+
+        return Err(err);
+    }
+
+    if !approved? {
+        interaction
+            .create_followup(
+                ctx.serenity(),
+                CreateInteractionResponseFollowup::new()
+                    .content(tr!(ctx, locales, "review-already-handled"))
+                    .ephemeral(true),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    interaction
+        .edit_response(
+            ctx.serenity(),
+            EditInteractionResponse::new()
+                .content(tr!(
+                    ctx,
+                    config.guild_locales(),
+                    "review-approved",
+                    member = user_id.mention().to_string(),
+                    moderator = interaction.user.to_string(),
+                ))
+                .components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(ctx, interaction))]
+pub async fn modal_interaction(
+    ctx: &impl Context,
+    interaction: &ModalInteraction,
+    decline: Decline,
+    user_id: UserId,
+) -> Result<()> {
+    let guild_id = interaction
+        .guild_id
+        .context("Interaction has no guild_id")?;
+    let config = ctx.config().guild(guild_id)?;
+    let locales = config.guild_locales();
+
+    if !is_moderator(interaction.member.as_ref()) {
+        return Ok(());
+    }
+
+    let reason = interaction
+        .data
+        .components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|component| match component {
+            ActionRowComponent::InputText(input_text) if input_text.custom_id == REASON_ID => {
+                input_text.value.clone()
+            }
+            _ => None,
+        })
+        .context("Missing field: reason")?;
+
+    let message_id = interaction
+        .message
+        .as_ref()
+        .context("Interaction has no message")?
+        .id;
+
+    // Claims the review, so that it isn't declined twice when moderators act at once.
+    let claimed = match decline {
+        Decline::Reject => persist::intro_review::take(ctx.db(), guild_id, user_id, message_id)
+            .await?
+            .is_some(),
+        Decline::RequestChanges => {
+            persist::intro_review::request_changes(ctx.db(), guild_id, user_id, message_id).await?
+        }
+    };
+
+    if !claimed {
+        interaction
+            .create_response(
+                ctx.serenity(),
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content(tr!(
+                            ctx,
+                            config.user_locales(&interaction.locale),
+                            "review-already-handled",
+                        ))
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    interaction
+        .create_response(ctx.serenity(), CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    let notified = decline_intro(ctx, guild_id, user_id, decline, &reason).await?;
+
+    let member = user_id.mention().to_string();
+    let moderator = interaction.user.to_string();
+    let mut content = match decline {
+        Decline::Reject => tr!(
+            ctx,
+            locales,
+            "review-rejected",
+            member = member,
+            moderator = moderator,
+            reason = reason,
+        ),
+        Decline::RequestChanges => tr!(
+            ctx,
+            locales,
+            "review-changes-requested",
+            member = member,
+            moderator = moderator,
+            reason = reason,
+        ),
+    };
+    if !notified {
+        content.push('\n');
+        content.push_str(&tr!(ctx, locales, "review-dm-failed"));
+    }
+
+    interaction
+        .edit_response(
+            ctx.serenity(),
+            EditInteractionResponse::new()
+                .content(content)
+                .components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Returns false if another moderator already handled the review.
+#[tracing::instrument(skip(ctx, moderator), fields(moderator.id = %moderator.id))]
+async fn approve_intro(
+    ctx: &impl Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    message_id: MessageId,
+    moderator: &User,
+) -> Result<bool> {
+    let mut tx = ctx.db().begin().await?;
+
+    // Deleting the review first locks it until the intro is published, so that it is only
+    // published once when moderators act at once.
+    let Some(review) = persist::intro_review::take(&mut *tx, guild_id, user_id, message_id).await?
+    else {
+        return Ok(false);
+    };
+
+    let mut member = guild_id.member(ctx.serenity(), user_id).await?;
+
+    intro::publish(
+        ctx,
+        &member,
+        &Intro {
+            fields: review.fields,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    if let Err(err) = unquarantine(ctx, &mut member).await {
+        // Otherwise they would be stuck under review without a pending review.
+        state::transition(ctx, guild_id, user_id, State::Welcomed).await?;
+        return Err(err);
+    }
+
+    info!(%guild_id, %user_id, "Approved intro");
+
+    Ok(true)
+}
+
+/// Notifies the member of a review that was already claimed.
+/// Returns whether the member could be notified.
+#[tracing::instrument(skip(ctx, reason))]
+async fn decline_intro(
+    ctx: &impl Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    decline: Decline,
+    reason: &str,
+) -> Result<bool> {
+    let config = ctx.config().guild(guild_id)?;
+    let locales = config.guild_locales();
+
+    let guild_name = guild_id
+        .name(ctx.serenity())
+        .context("Guild not available in cache")?;
+
+    let intro_location = persist::welcome_message::get(ctx.db(), guild_id, user_id)
+        .await?
+        .map_or_else(
+            || {
+                tr!(
+                    ctx,
+                    locales,
+                    "review-dm-location",
+                    guild = guild_name.clone()
+                )
+            },
+            |(channel_id, message_id)| message_id.link(channel_id, Some(guild_id)).to_string(),
+        );
+
+    let message = match decline {
+        Decline::Reject => tr!(
+            ctx,
+            locales,
+            "review-rejected-dm",
+            guild = guild_name,
+            reason = reason,
+            link = intro_location,
+        ),
+        Decline::RequestChanges => tr!(
+            ctx,
+            locales,
+            "review-changes-requested-dm",
+            guild = guild_name,
+            reason = reason,
+            link = intro_location,
+        ),
+    };
+
+    set_welcome_awaiting_review(ctx, guild_id, user_id, false).await?;
+    state::transition(ctx, guild_id, user_id, State::Welcomed).await?;
+
+    info!(%guild_id, %user_id, ?decline, "Declined intro");
+
+    let dm_result = async {
+        let dm_channel = user_id.create_dm_channel(ctx.serenity()).await?;
+        dm_channel.say(ctx.serenity(), message).await
+    }
+    .await;
+
+    if let Err(err) = dm_result {
+        warn!(error = ?err, "Could not DM member");
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+/// Withdraws the pending review, for example when the member leaves.
+#[tracing::instrument(skip(ctx))]
+pub async fn cancel(ctx: &impl Context, guild_id: GuildId, user_id: UserId) -> Result<()> {
+    let config = ctx.config().guild(guild_id)?;
+
+    let mut tx = ctx.db().begin().await?;
+
+    if let Some(review) = persist::intro_review::get(&mut *tx, guild_id, user_id).await? {
+        if !review.changes_requested {
+            review
+                .channel_id
+                .edit_message(
+                    ctx.serenity(),
+                    review.message_id,
+                    EditMessage::new()
+                        .content(tr!(
+                            ctx,
+                            config.guild_locales(),
+                            "review-withdrawn",
+                            member = user_id.mention().to_string(),
+                        ))
+                        .components(vec![]),
+                )
+                .await
+                .map(|_| ())
+                .or_else(|err| {
+                    if is_http_not_found(&err) {
+                        Ok(())
+                    } else {
+                        Err(err)
+                    }
+                })?;
+        }
+
+        persist::intro_review::delete(&mut *tx, guild_id, user_id).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,324 @@
+use std::{collections::HashSet, fmt::Write, future};
+
+use anyhow::Context as _;
+use futures::TryStreamExt;
+use serenity::all::{
+    ButtonStyle,
+    ComponentInteraction,
+    CreateActionRow,
+    CreateButton,
+    CreateEmbed,
+    CreateInteractionResponse,
+    CreateInteractionResponseMessage,
+    CreateMessage,
+    GuildId,
+    Member,
+    UserId,
+};
+use tracing::{info, warn};
+
+use super::{connected_configured_guilds, create_onboarding_prompt, intro, persist, report};
+use crate::{config::GuildConfig, context::Context, error::Result, l10n::tr};
+
+const ACCEPT_ID: &str = "onboarding_accept_rules";
+
+/// The custom ID of the accept button includes the guild, because it's also sent in DMs,
+/// and the version, so that outdated buttons don't count as accepting the current rules.
+fn accept_custom_id(guild_id: GuildId, version: i32) -> String {
+    format!("{ACCEPT_ID}:{guild_id}:{version}")
+}
+
+pub fn parse_custom_id(custom_id: &str) -> Option<(GuildId, i32)> {
+    let (id, rest) = custom_id.split_once(':')?;
+    if id != ACCEPT_ID {
+        return None;
+    }
+
+    let (guild_id, version) = rest.split_once(':')?;
+    let guild_id = guild_id.parse::<u64>().ok().filter(|&id| id != 0)?;
+    let version = version.parse().ok()?;
+
+    Some((GuildId::new(guild_id), version))
+}
+
+pub fn create_button(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_id: GuildId,
+    locales: &[&str],
+) -> CreateButton {
+    CreateButton::new(accept_custom_id(guild_id, config.rules.version))
+        .label(tr!(ctx, locales, "rules-button"))
+        .emoji('📜')
+        .style(ButtonStyle::Secondary)
+}
+
+pub fn render(config: &GuildConfig) -> String {
+    config
+        .rules
+        .template
+        .render(&[("coc_url", &config.coc_url)])
+}
+
+fn create_embed(config: &GuildConfig) -> CreateEmbed {
+    CreateEmbed::new().description(render(config))
+}
+
+/// Whether the member agreed to the current version of the rules.
+pub async fn is_accepted(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<bool> {
+    let accepted =
+        persist::rules_acceptance::get(ctx.db(), guild_id, user_id, config.rules.version)
+            .await?
+            .is_some();
+
+    Ok(accepted)
+}
+
+/// Asks the member to agree to the rules before introducing themselves.
+pub fn create_prompt(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_id: GuildId,
+    user_locale: &str,
+) -> CreateInteractionResponseMessage {
+    let locales = config.user_locales(user_locale);
+
+    CreateInteractionResponseMessage::new()
+        .content(tr!(ctx, locales, "rules-required"))
+        .embed(create_embed(config))
+        .components(vec![CreateActionRow::Buttons(vec![create_button(
+            ctx, config, guild_id, &locales,
+        )])])
+        .ephemeral(true)
+}
+
+#[tracing::instrument(skip(ctx, interaction))]
+pub async fn component_interaction(
+    ctx: &impl Context,
+    interaction: &ComponentInteraction,
+    guild_id: GuildId,
+    version: i32,
+) -> Result<()> {
+    let config = ctx.config().guild(guild_id)?;
+    let locales = config.user_locales(&interaction.locale);
+
+    if version != config.rules.version {
+        let response = create_prompt(ctx, config, guild_id, &interaction.locale).content(tr!(
+            ctx,
+            locales,
+            "rules-outdated"
+        ));
+        interaction
+            .create_response(ctx.serenity(), CreateInteractionResponse::Message(response))
+            .await?;
+
+        return Ok(());
+    }
+
+    persist::rules_acceptance::set(
+        ctx.db(),
+        guild_id,
+        interaction.user.id,
+        version,
+        chrono::Utc::now(),
+    )
+    .await?;
+
+    info!(%guild_id, %interaction.user.id, version, "Member accepted the rules");
+
+    let is_quarantined = interaction
+        .member
+        .as_ref()
+        .is_some_and(|member| member.roles.contains(&config.quarantine_role));
+
+    let response = if is_quarantined {
+        match create_onboarding_prompt(
+            ctx,
+            config,
+            guild_id,
+            interaction.user.id,
+            &interaction.locale,
+        )
+        .await?
+        {
+            Some(prompt) => prompt,
+            None => CreateInteractionResponseMessage::new()
+                .content(tr!(ctx, locales, "rules-accepted-intro"))
+                .components(vec![CreateActionRow::Buttons(vec![intro::create_button(
+                    ctx, config,
+                )])])
+                .ephemeral(true),
+        }
+    } else {
+        CreateInteractionResponseMessage::new()
+            .content(tr!(ctx, locales, "rules-accepted"))
+            .ephemeral(true)
+    };
+
+    interaction
+        .create_response(ctx.serenity(), CreateInteractionResponse::Message(response))
+        .await?;
+
+    Ok(())
+}
+
+/// Members past onboarding who haven't agreed to the current rules.
+/// Quarantined members are asked to agree as part of onboarding.
+async fn get_members_not_accepted(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_id: GuildId,
+) -> Result<Vec<Member>> {
+    let accepted: HashSet<UserId> =
+        persist::rules_acceptance::get_user_ids(ctx.db(), guild_id, config.rules.version).await?;
+
+    let members = guild_id
+        .members_iter(ctx.serenity())
+        .try_filter(|member| {
+            future::ready(
+                !member.user.bot
+                    && !member.roles.contains(&config.quarantine_role)
+                    && !accepted.contains(&member.user.id),
+            )
+        })
+        .try_collect()
+        .await?;
+
+    Ok(members)
+}
+
+#[tracing::instrument(skip(ctx, config))]
+async fn prompt_members(ctx: &impl Context, config: &GuildConfig, guild_id: GuildId) -> Result<()> {
+    let guild_name = guild_id
+        .name(ctx.serenity())
+        .context("Guild not available in cache")?;
+    let locales = config.guild_locales();
+
+    let members = get_members_not_accepted(ctx, config, guild_id).await?;
+
+    for member in &members {
+        let dm_result = async {
+            let dm_channel = member.user.create_dm_channel(ctx.serenity()).await?;
+            dm_channel
+                .send_message(
+                    ctx.serenity(),
+                    CreateMessage::new()
+                        .content(tr!(
+                            ctx,
+                            locales,
+                            "rules-changed-dm",
+                            guild = guild_name.as_str(),
+                        ))
+                        .embed(create_embed(config))
+                        .components(vec![CreateActionRow::Buttons(vec![create_button(
+                            ctx, config, guild_id, &locales,
+                        )])]),
+                )
+                .await
+        }
+        .await;
+
+        // Members who can't be reached show up in the report.
+        if let Err(err) = dm_result {
+            warn!(error = ?err, %member.user.id, "Could not DM member");
+        }
+    }
+
+    info!(
+        n_members = members.len(),
+        "Asked members to accept the new rules"
+    );
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(ctx, config))]
+async fn report_members(
+    ctx: &impl Context,
+    config: &GuildConfig,
+    guild_id: GuildId,
+    published_at: chrono::DateTime<chrono::Utc>,
+) -> Result<()> {
+    let locales = config.guild_locales();
+
+    let members = get_members_not_accepted(ctx, config, guild_id).await?;
+    if members.is_empty() {
+        return Ok(());
+    }
+
+    let mut text = String::new();
+    writeln!(
+        text,
+        "{}",
+        tr!(
+            ctx,
+            locales,
+            "rules-report",
+            count = members.len(),
+            version = config.rules.version,
+            published = format!("<t:{}:R>", published_at.timestamp()),
+        ),
+    )?;
+    for member in &members {
+        writeln!(
+            text,
+            "{}",
+            tr!(
+                ctx,
+                locales,
+                "rules-report-member",
+                member = member.to_string(),
+                tag = member.user.tag(),
+            ),
+        )?;
+    }
+
+    report(ctx, config, text, "rules_not_accepted.md").await
+}
+
+/// Asks members to agree to new versions of the rules, and reports who hasn't after the deadline.
+pub async fn check_rules(ctx: &impl Context) -> Result<()> {
+    let now = chrono::Utc::now();
+
+    for (guild_id, config) in connected_configured_guilds(ctx) {
+        let version = config.rules.version;
+
+        match persist::rules_version::get(ctx.db(), guild_id, version).await? {
+            None => {
+                // Members who joined before the bot knew about any rules agreed to them
+                // through the old flow, so they aren't asked again.
+                let is_first = !persist::rules_version::any(ctx.db(), guild_id).await?;
+
+                persist::rules_version::add(
+                    ctx.db(),
+                    guild_id,
+                    version,
+                    now,
+                    is_first.then_some(now),
+                )
+                .await?;
+
+                if !is_first {
+                    prompt_members(ctx, config, guild_id).await?;
+                }
+            }
+
+            Some(rules_version)
+                if rules_version.reported_at.is_none()
+                    && rules_version.published_at + config.rules.reaccept_deadline <= now =>
+            {
+                report_members(ctx, config, guild_id, rules_version.published_at).await?;
+                persist::rules_version::set_reported(ctx.db(), guild_id, version, now).await?;
+            }
+
+            Some(_) => {}
+        }
+    }
+
+    Ok(())
+}
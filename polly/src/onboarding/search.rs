@@ -0,0 +1,166 @@
+use std::{fmt::Write, time::Duration};
+
+use anyhow::Context as _;
+use poise::CreateReply;
+use serenity::all::{
+    ButtonStyle,
+    ComponentInteractionCollector,
+    CreateActionRow,
+    CreateButton,
+    CreateInteractionResponse,
+    CreateInteractionResponseMessage,
+    GuildId,
+    Mentionable,
+    UserId,
+};
+
+use super::persist;
+use crate::{PoiseApplicationContext, context::Context, error::Result, l10n::tr};
+
+const PAGE_SIZE: usize = 10;
+
+/// How long the page buttons keep working.
+const TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+async fn write_page(
+    ctx: PoiseApplicationContext<'_>,
+    locales: &[&str],
+    guild_id: GuildId,
+    query: &str,
+    user_ids: &[UserId],
+    page: usize,
+) -> Result<String> {
+    let start = page * PAGE_SIZE;
+    let end = (start + PAGE_SIZE).min(user_ids.len());
+
+    let mut text = String::new();
+    writeln!(
+        text,
+        "{}",
+        tr!(
+            &ctx,
+            locales,
+            "search-title",
+            query = query,
+            first = start + 1,
+            last = end,
+            count = user_ids.len(),
+        ),
+    )?;
+
+    for (i, &user_id) in user_ids[start..end].iter().enumerate() {
+        write!(text, "{}. {}", start + i + 1, user_id.mention())?;
+        if let Some((channel_id, message_id)) =
+            persist::intro_message::get(ctx.db(), guild_id, user_id).await?
+        {
+            write!(text, " {}", message_id.link(channel_id, Some(guild_id)))?;
+        }
+        writeln!(text)?;
+    }
+
+    Ok(text)
+}
+
+fn create_components(
+    ctx: PoiseApplicationContext<'_>,
+    locales: &[&str],
+    page: usize,
+    n_pages: usize,
+) -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{}prev", ctx.id()))
+            .label(tr!(&ctx, locales, "search-previous"))
+            .style(ButtonStyle::Secondary)
+            .disabled(page == 0),
+        CreateButton::new(format!("{}next", ctx.id()))
+            .label(tr!(&ctx, locales, "search-next"))
+            .style(ButtonStyle::Secondary)
+            .disabled(page + 1 >= n_pages),
+    ])]
+}
+
+/// Search introductions
+#[poise::command(guild_only, slash_command)]
+#[tracing::instrument(
+    fields(
+        ctx.id = ctx.id(),
+        ctx.guild_id = %ctx.guild_id().unwrap_or_default(),
+        ctx.author.id = %ctx.author().id,
+    ),
+    skip(ctx),
+)]
+pub async fn search(
+    ctx: PoiseApplicationContext<'_>,
+    #[description = "Words to search for, for example a city or a language"]
+    #[max_length = 100]
+    query: String,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx.guild_id().context("Context has no guild_id")?;
+    let config = ctx.config().guild(guild_id)?;
+    let locales = config.user_locales(&ctx.interaction.locale);
+
+    let user_ids: Vec<UserId> = {
+        let matches = persist::intro::search(ctx.db(), guild_id, &query).await?;
+
+        // Intros of members who left stay in the database, but shouldn't show up.
+        let guild = ctx
+            .serenity_context()
+            .cache
+            .guild(guild_id)
+            .context("Guild not available in cache")?;
+        matches
+            .into_iter()
+            .filter(|user_id| guild.members.contains_key(user_id))
+            .collect()
+    };
+
+    if user_ids.is_empty() {
+        ctx.say(tr!(&ctx, locales, "search-none", query = query.as_str()))
+            .await?;
+        return Ok(());
+    }
+
+    let n_pages = user_ids.len().div_ceil(PAGE_SIZE);
+    let mut page = 0;
+
+    ctx.send(
+        CreateReply::default()
+            .content(write_page(ctx, &locales, guild_id, &query, &user_ids, page).await?)
+            .components(create_components(ctx, &locales, page, n_pages))
+            .ephemeral(true),
+    )
+    .await?;
+
+    let ctx_id = ctx.id().to_string();
+    while let Some(press) = ComponentInteractionCollector::new(ctx.serenity_context())
+        .filter({
+            let ctx_id = ctx_id.clone();
+            move |press| press.data.custom_id.starts_with(&ctx_id)
+        })
+        .timeout(TIMEOUT)
+        .await
+    {
+        match &press.data.custom_id[ctx_id.len()..] {
+            "prev" => page = page.saturating_sub(1),
+            "next" => page = (page + 1).min(n_pages - 1),
+            _ => continue,
+        }
+
+        press
+            .create_response(
+                ctx.serenity_context(),
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .content(
+                            write_page(ctx, &locales, guild_id, &query, &user_ids, page).await?,
+                        )
+                        .components(create_components(ctx, &locales, page, n_pages)),
+                ),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use std::fmt::Write;
+
+use anyhow::Context as _;
+use chrono::{DateTime, Utc};
+use serenity::all::{GuildId, Member, User, UserId};
+use sqlx::PgConnection;
+use tracing::{info, warn};
+
+use super::{
+    persist::{self, lifecycle::Stage, state::State},
+    reply_long,
+};
+use crate::{PoiseApplicationContext, context::Context, error::Result, l10n::tr};
+
+/// Whether a member can go from one state to the other.
+fn is_valid(from: Option<State>, to: State) -> bool {
+    match from {
+        // The first state recorded can be any.
+        None
+        | Some(State::Joined | State::Welcomed | State::IntroSubmitted | State::UnderReview) => {
+            true
+        }
+        // Members only go back to onboarding by being quarantined again.
+        Some(State::Member) => matches!(
+            to,
+            State::Joined | State::Welcomed | State::Kicked | State::Left
+        ),
+        Some(State::Kicked | State::Left) => to == State::Joined,
+    }
+}
+
+/// The lifecycle stage that the state is counted as in the stats.
+fn lifecycle_stage(state: State) -> Option<Stage> {
+    match state {
+        State::Joined | State::UnderReview => None,
+        State::Welcomed => Some(Stage::Welcomed),
+        State::IntroSubmitted => Some(Stage::IntroSubmitted),
+        State::Member => Some(Stage::Unquarantined),
+        State::Kicked => Some(Stage::Kicked),
+        State::Left => Some(Stage::Left),
+    }
+}
+
+/// Moves the member to the state, recording the transition and the lifecycle stage.
+/// Invalid transitions are logged and ignored.
+#[tracing::instrument(skip(ctx))]
+pub async fn transition(
+    ctx: &impl Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    to: State,
+) -> Result<()> {
+    let mut tx = ctx.db().begin().await?;
+    record(&mut tx, guild_id, user_id, to, chrono::Utc::now()).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// The database part of `transition`, for running in a transaction with other changes.
+/// The state stays locked until the transaction ends.
+pub async fn record(
+    conn: &mut PgConnection,
+    guild_id: GuildId,
+    user_id: UserId,
+    to: State,
+    at: DateTime<Utc>,
+) -> Result<()> {
+    let from = persist::state::get_for_update(&mut *conn, guild_id, user_id).await?;
+
+    // Every join counts, even if the member's leaving was missed.
+    let unchanged = from == Some(to) && to != State::Joined;
+    // Kicking a member also makes them leave.
+    let kicked = from == Some(State::Kicked) && to == State::Left;
+    if unchanged || kicked {
+        return Ok(());
+    }
+
+    if !is_valid(from, to) {
+        warn!(?from, ?to, "Invalid onboarding state transition");
+        return Ok(());
+    }
+
+    persist::state::set(&mut *conn, guild_id, user_id, from, to, at).await?;
+
+    if to == State::Joined {
+        persist::lifecycle::add(&mut *conn, guild_id, user_id, at).await?;
+    } else if let Some(stage) = lifecycle_stage(to) {
+        persist::lifecycle::set(&mut *conn, guild_id, user_id, stage, at).await?;
+    }
+
+    info!(?from, ?to, "Onboarding state changed");
+
+    Ok(())
+}
+
+/// Catches up with moderators removing the quarantine role by hand.
+pub async fn sync_quarantine_role(
+    ctx: &impl Context,
+    old: Option<&Member>,
+    member: &Member,
+) -> Result<()> {
+    let config = ctx.config().guild(member.guild_id)?;
+
+    let removed = !member.roles.contains(&config.quarantine_role)
+        && old.is_none_or(|old| old.roles.contains(&config.quarantine_role));
+    if !removed {
+        return Ok(());
+    }
+
+    let state = persist::state::get(ctx.db(), member.guild_id, member.user.id)
+        .await?
+        .map(|(state, _)| state);
+
+    if matches!(
+        state,
+        Some(State::Welcomed | State::IntroSubmitted | State::UnderReview)
+    ) {
+        transition(ctx, member.guild_id, member.user.id, State::Member).await?;
+    }
+
+    Ok(())
+}
+
+fn state_name(ctx: &impl Context, locales: &[&str], state: State) -> String {
+    match state {
+        State::Joined => tr!(ctx, locales, "state-joined"),
+        State::Welcomed => tr!(ctx, locales, "state-welcomed"),
+        State::IntroSubmitted => tr!(ctx, locales, "state-intro-submitted"),
+        State::UnderReview => tr!(ctx, locales, "state-under-review"),
+        State::Member => tr!(ctx, locales, "state-member"),
+        State::Kicked => tr!(ctx, locales, "state-kicked"),
+        State::Left => tr!(ctx, locales, "state-left"),
+    }
+}
+
+/// Show where a member is in onboarding, and how they got there
+#[poise::command(guild_only, slash_command)]
+#[tracing::instrument(
+    fields(
+        ctx.id = ctx.id(),
+        ctx.guild_id = %ctx.guild_id().unwrap_or_default(),
+        ctx.author.id = %ctx.author().id,
+        %user.id,
+    ),
+    skip(ctx, user),
+)]
+pub async fn state(
+    ctx: PoiseApplicationContext<'_>,
+    #[description = "Member whose state to show"] user: User,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx.guild_id().context("Context has no guild_id")?;
+    let config = ctx.config().guild(guild_id)?;
+    let locales = config.user_locales(&ctx.interaction.locale);
+
+    let Some((state, updated_at)) = persist::state::get(ctx.db(), guild_id, user.id).await? else {
+        ctx.say(tr!(&ctx, locales, "state-none", user = user.to_string()))
+            .await?;
+        return Ok(());
+    };
+
+    let mut text = String::new();
+    writeln!(
+        text,
+        "{}",
+        tr!(
+            &ctx,
+            locales,
+            "state-current",
+            user = user.to_string(),
+            state = state_name(&ctx, &locales, state),
+            since = format!("<t:{}:R>", updated_at.timestamp()),
+        ),
+    )?;
+
+    for transition in persist::state::get_transitions(ctx.db(), guild_id, user.id).await? {
+        let to = state_name(&ctx, &locales, transition.to);
+        let change = transition.from.map_or_else(
+            || to.clone(),
+            |from| format!("{} → {to}", state_name(&ctx, &locales, from)),
+        );
+        writeln!(text, "- <t:{}:f> {change}", transition.at.timestamp())?;
+    }
+
+    reply_long(ctx, text, "onboarding_state.md").await
+}
@@ -0,0 +1,119 @@
+use std::{fmt::Write, time::Duration};
+
+use anyhow::Context as _;
+use chrono::{Datelike, NaiveTime, TimeDelta};
+
+use super::{
+    format_duration,
+    persist::{self, lifecycle::Funnel},
+    reply_long,
+};
+use crate::{PoiseApplicationContext, context::Context, error::Result, l10n::tr};
+
+fn percent(count: i64, total: i64) -> i64 {
+    if total == 0 { 0 } else { count * 100 / total }
+}
+
+fn write_funnel(
+    ctx: &impl Context,
+    locales: &[&str],
+    w: &mut impl Write,
+    funnel: &Funnel,
+) -> Result<()> {
+    let title = funnel.week.map_or_else(
+        || tr!(ctx, locales, "stats-total"),
+        |week| {
+            tr!(
+                ctx,
+                locales,
+                "stats-week",
+                week = format!("<t:{}:D>", week.timestamp()),
+            )
+        },
+    );
+    writeln!(w, "{title}")?;
+
+    writeln!(
+        w,
+        "{}",
+        tr!(
+            ctx,
+            locales,
+            "stats-funnel",
+            joined = funnel.joined,
+            welcomed = funnel.welcomed,
+            introduced = funnel.intro_submitted,
+            introduced_percent = percent(funnel.intro_submitted, funnel.joined),
+            unquarantined = funnel.unquarantined,
+            unquarantined_percent = percent(funnel.unquarantined, funnel.joined),
+            kicked = funnel.kicked,
+            left = funnel.left,
+        ),
+    )?;
+
+    if let Some(secs) = funnel.median_secs_to_intro {
+        writeln!(
+            w,
+            "{}",
+            tr!(
+                ctx,
+                locales,
+                "stats-median",
+                duration = format_duration(ctx, locales, Duration::from_secs(secs.unsigned_abs())),
+            ),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Show how many members who joined recently completed onboarding
+#[poise::command(guild_only, slash_command)]
+#[tracing::instrument(
+    fields(
+        ctx.id = ctx.id(),
+        ctx.guild_id = %ctx.guild_id().unwrap_or_default(),
+        ctx.author.id = %ctx.author().id,
+    ),
+    skip(ctx),
+)]
+pub async fn stats(
+    ctx: PoiseApplicationContext<'_>,
+    #[description = "Number of weeks to show, including this one (default: 8)"]
+    #[min = 1]
+    #[max = 52]
+    weeks: Option<u32>,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx.guild_id().context("Context has no guild_id")?;
+    let config = ctx.config().guild(guild_id)?;
+    let locales = config.user_locales(&ctx.interaction.locale);
+    let weeks = weeks.unwrap_or(8);
+
+    // Start at the beginning of a week, like the weeks in the funnels.
+    let today = chrono::Utc::now().date_naive();
+    let since = (today
+        - TimeDelta::days(i64::from(today.weekday().num_days_from_monday()))
+        - TimeDelta::weeks(i64::from(weeks - 1)))
+    .and_time(NaiveTime::MIN)
+    .and_utc();
+
+    let funnels = persist::lifecycle::get_funnels(ctx.db(), guild_id, since).await?;
+
+    // The total is there even if nobody joined.
+    if funnels.iter().all(|funnel| funnel.joined == 0) {
+        ctx.say(tr!(&ctx, locales, "stats-none", weeks = weeks))
+            .await?;
+        return Ok(());
+    }
+
+    let mut text = String::new();
+    writeln!(text, "{}", tr!(&ctx, locales, "stats-title", weeks = weeks),)?;
+    for funnel in &funnels {
+        writeln!(text)?;
+        write_funnel(&ctx, &locales, &mut text, funnel)?;
+    }
+
+    reply_long(ctx, text, "onboarding_stats.md").await
+}
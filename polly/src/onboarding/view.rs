@@ -0,0 +1,84 @@
+use anyhow::Context as _;
+use poise::CreateReply;
+use serenity::all::User;
+
+use super::{intro, persist};
+use crate::{PoiseApplicationContext, context::Context, error::Result, l10n::tr};
+
+async fn reply_with_intro(ctx: PoiseApplicationContext<'_>, user: &User) -> Result<()> {
+    let guild_id = ctx.guild_id().context("Context has no guild_id")?;
+    let config = ctx.config().guild(guild_id)?;
+    let locales = config.user_locales(&ctx.interaction.locale);
+
+    let Some(intro) = intro::get(&ctx, guild_id, user.id).await? else {
+        ctx.send(
+            CreateReply::default()
+                .content(tr!(&ctx, locales, "view-none", user = user.to_string()))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let content = persist::intro_message::get(ctx.db(), guild_id, user.id)
+        .await?
+        .map_or_else(
+            || tr!(&ctx, locales, "view-unpublished"),
+            |(channel_id, message_id)| {
+                tr!(
+                    &ctx,
+                    locales,
+                    "view-link",
+                    link = message_id.link(channel_id, Some(guild_id)).to_string(),
+                )
+            },
+        );
+
+    ctx.send(
+        CreateReply::default()
+            .content(content)
+            .embed(intro::create_embed(
+                config,
+                user,
+                user.display_name(),
+                &intro,
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Show a member's introduction
+#[poise::command(guild_only, slash_command)]
+#[tracing::instrument(
+    fields(
+        ctx.id = ctx.id(),
+        ctx.guild_id = %ctx.guild_id().unwrap_or_default(),
+        ctx.author.id = %ctx.author().id,
+        %user.id,
+    ),
+    skip(ctx, user),
+)]
+pub async fn view(
+    ctx: PoiseApplicationContext<'_>,
+    #[description = "Member whose introduction to show"] user: User,
+) -> Result<()> {
+    reply_with_intro(ctx, &user).await
+}
+
+/// Show the member's introduction
+#[poise::command(context_menu_command = "View intro", guild_only)]
+#[tracing::instrument(
+    fields(
+        ctx.id = ctx.id(),
+        ctx.guild_id = %ctx.guild_id().unwrap_or_default(),
+        ctx.author.id = %ctx.author().id,
+        %user.id,
+    ),
+    skip(ctx, user),
+)]
+pub async fn view_intro(ctx: PoiseApplicationContext<'_>, user: User) -> Result<()> {
+    reply_with_intro(ctx, &user).await
+}
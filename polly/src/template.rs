@@ -0,0 +1,87 @@
+use std::fmt;
+
+/// Text with `{placeholder}`s, which are checked when the template is parsed.
+/// Literal braces are written as `{{` and `}}`.
+#[derive(Debug)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug)]
+enum Part {
+    Text(String),
+    Placeholder(String),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    #[error("unknown placeholder {{{0}}}, expected one of {1:?}")]
+    UnknownPlaceholder(String, &'static [&'static str]),
+    #[error("unclosed {{")]
+    Unclosed,
+    #[error("unmatched }}")]
+    Unmatched,
+}
+
+impl Template {
+    pub fn parse(source: &str, placeholders: &'static [&'static str]) -> Result<Self, ParseError> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = source.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.next_if_eq(&'{').is_some() => text.push('{'),
+                '}' if chars.next_if_eq(&'}').is_some() => text.push('}'),
+
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(ParseError::Unclosed),
+                        }
+                    }
+
+                    if !placeholders.contains(&name.as_str()) {
+                        return Err(ParseError::UnknownPlaceholder(name, placeholders));
+                    }
+
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Placeholder(name));
+                }
+
+                '}' => return Err(ParseError::Unmatched),
+
+                c => text.push(c),
+            }
+        }
+
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        Ok(Template { parts })
+    }
+
+    /// Fills in the placeholders. Any placeholder without a value is left empty.
+    pub fn render(&self, values: &[(&str, &dyn fmt::Display)]) -> String {
+        let mut rendered = String::new();
+
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => rendered.push_str(text),
+                Part::Placeholder(name) => {
+                    if let Some((_, value)) = values.iter().find(|(key, _)| key == name) {
+                        rendered.push_str(&value.to_string());
+                    }
+                }
+            }
+        }
+
+        rendered
+    }
+}
@@ -3,15 +3,14 @@
 # We still have to declare each message resource separately, so that we can
 # specify the order with dependencies.
 locals {
-  rules = <<-EOT
-    Click the :white_check_mark: below this message to agree to these rules and gain access to the rest of the server.
-
-    **Rules**
-
-    1. You must be at least 18 years old to be a member of this server.
-    2. Always follow the Code of Conduct.
-    3. Speak English in the common channels.
-  EOT
+  # The same as in polly.toml.
+  coc_url = "https://polyam.eu/coc.html"
+
+  # Rendered from Polly's template, so that the rules posted here are the ones members agree to.
+  rules = join("\n\n", [
+    "Polly asks every new member to agree to these rules before they can introduce themselves, and asks everyone again when the rules change.",
+    replace(chomp(file("${path.module}/../polly/templates/rules.md")), "{coc_url}", local.coc_url),
+  ])
 
   code_of_conduct_title = "Code of Conduct"
 